@@ -2,69 +2,326 @@ use std::fs;
 use std::path::Path;
 use std::str::FromStr;
 
-fn parse(filename: &Path) -> Result<Vec<i32>, String> {
-    let mut numbers: Result<Vec<i32>, _> = fs::read_to_string(filename)
-        .map_err(|err| format!("Failed to read data for day 01: {}", err))?
-        .split_ascii_whitespace()
-        .map(|token| i32::from_str(token).map_err(|err| format!("Invalid data in file for day 01: {}", err)))
-        .collect();
+use num_bigint::BigInt;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Mode {
+    First,
+    All,
+    Count,
+}
+
+impl FromStr for Mode {
+    type Err = String;
 
-    if let Ok(nums) = &mut numbers {
-        nums.sort();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "first" => Ok(Mode::First),
+            "all" => Ok(Mode::All),
+            "count" => Ok(Mode::Count),
+            _ => Err(format!("Unknown k-sum mode '{}', expected first, all or count", s)),
+        }
     }
+}
 
-    numbers
+#[derive(Clone, Debug)]
+struct Entry {
+    value: i64,
+    // 0-based position in the expense report
+    index: usize,
 }
 
-pub fn part01(filename: &Path) -> Result<String, String> {
-    let numbers = parse(filename)?;
-
-    for i in 0..numbers.len() {
-        let first = &numbers[i];
-        for j in ((i + 1)..numbers.len()).rev() {
-            let second = &numbers[j];
-            if first + second > 2020 {
-                continue;
-            } else if first + second == 2020 {
-                return Ok(format!("{} * {} = {}", first, second, first * second));
-            } else {
-                break;
-            }
-        }
+#[derive(Clone, Debug)]
+struct Solution {
+    entries: Vec<Entry>,
+}
+
+impl Solution {
+    fn product(&self) -> BigInt {
+        self.entries.iter().map(|entry| BigInt::from(entry.value)).product()
     }
 
-    Err(format!("{:?}", "Failed to find solution for day 01, part 1..."))
+    fn describe(&self) -> String {
+        format!(
+            "{} = {}",
+            self.entries
+                .iter()
+                .map(|entry| entry.value.to_string())
+                .collect::<Vec<String>>()
+                .join(" * "),
+            self.product()
+        )
+    }
 }
 
-pub fn part02(filename: &Path) -> Result<String, String> {
-    let numbers = parse(filename)?;
-
-    for i in 0..numbers.len() {
-        let first = &numbers[i];
-        for j in ((i + 1)..numbers.len()).rev() {
-            let second = &numbers[j];
-            if first + second >= 2020 {
-                continue;
-            } else {
-                for k in 0..numbers.len() {
-                    let third = &numbers[k];
-                    if first + second + third < 2020 {
-                        continue;
-                    } else if first + second + third == 2020 {
-                        return Ok(format!(
-                            "{} * {} * {} = {}",
-                            first,
-                            second,
-                            third,
-                            first * second * third
-                        ));
-                    } else {
+// expense report entries sorted by value, remembering where each one came from
+struct Report {
+    entries: Vec<Entry>,
+}
+
+impl Report {
+    fn new(numbers: &[i64]) -> Self {
+        let mut entries: Vec<Entry> = numbers
+            .iter()
+            .enumerate()
+            .map(|(index, &value)| Entry { value, index })
+            .collect();
+        entries.sort_by_key(|entry| (entry.value, entry.index));
+
+        Report { entries }
+    }
+
+    fn solve(&self, k: usize, target: i64, mode: Mode) -> (Vec<Solution>, u128) {
+        let mut solutions = Vec::new();
+        if k > self.entries.len() {
+            return (solutions, 0);
+        }
+        if mode == Mode::Count {
+            return (solutions, self.count(0, k, target as i128));
+        }
+
+        let mut chosen = Vec::with_capacity(k.min(self.entries.len()));
+        self.visit(0, k, target as i128, &mut chosen, &mut |chosen| {
+            let mut entries: Vec<Entry> = chosen.iter().map(|&idx| self.entries[idx].clone()).collect();
+            entries.sort_by_key(|entry| (entry.value, entry.index));
+            solutions.push(Solution { entries });
+            mode == Mode::All
+        });
+        let count = solutions.len() as u128;
+
+        (solutions, count)
+    }
+
+    // smallest and largest sum of `k` entries taken from `start..`
+    fn bounds(&self, start: usize, k: usize) -> (i128, i128) {
+        let values = &self.entries[start..];
+        let lowest = values[..k].iter().map(|entry| entry.value as i128).sum();
+        let highest = values[values.len() - k..].iter().map(|entry| entry.value as i128).sum();
+        (lowest, highest)
+    }
+
+    // length of the run of equal values starting at `idx` going forwards (or backwards)
+    fn run_length(&self, idx: usize, forwards: bool) -> usize {
+        let value = self.entries[idx].value;
+        if forwards {
+            self.entries[idx..]
+                .iter()
+                .take_while(|entry| entry.value == value)
+                .count()
+        } else {
+            self.entries[..=idx]
+                .iter()
+                .rev()
+                .take_while(|entry| entry.value == value)
+                .count()
+        }
+    }
+
+    // visits every set of `k` distinct indices from `start..` summing to `target`, stops when `emit` returns false
+    fn visit(
+        &self,
+        start: usize,
+        k: usize,
+        target: i128,
+        chosen: &mut Vec<usize>,
+        emit: &mut dyn FnMut(&[usize]) -> bool,
+    ) -> bool {
+        let len = self.entries.len();
+        if k == 0 {
+            return target != 0 || emit(chosen);
+        }
+        if len - start < k {
+            return true;
+        }
+        let (lowest, highest) = self.bounds(start, k);
+        if target < lowest || highest < target {
+            return true;
+        }
+
+        if k == 1 {
+            for idx in start..len {
+                if self.entries[idx].value as i128 == target {
+                    chosen.push(idx);
+                    let proceed = emit(chosen);
+                    chosen.pop();
+                    if !proceed {
+                        return false;
+                    }
+                }
+            }
+            return true;
+        }
+
+        if k == 2 {
+            let (mut lo, mut hi) = (start, len - 1);
+            while lo < hi {
+                let sum = self.entries[lo].value as i128 + self.entries[hi].value as i128;
+                if sum < target {
+                    lo += 1;
+                } else if sum > target {
+                    hi -= 1;
+                } else {
+                    // every pair of indices drawn from the two runs of equal values is a solution
+                    let same = self.entries[lo].value == self.entries[hi].value;
+                    let low_end = if same { hi + 1 } else { lo + self.run_length(lo, true) };
+                    let high_start = if same { lo } else { hi + 1 - self.run_length(hi, false) };
+                    for first in lo..low_end {
+                        for second in high_start.max(first + 1)..=hi {
+                            chosen.push(first);
+                            chosen.push(second);
+                            let proceed = emit(chosen);
+                            chosen.truncate(chosen.len() - 2);
+                            if !proceed {
+                                return false;
+                            }
+                        }
+                    }
+                    if same {
                         break;
                     }
+                    lo = low_end;
+                    hi = high_start - 1;
                 }
             }
+            return true;
         }
+
+        for idx in start..=(len - k) {
+            let value = self.entries[idx].value as i128;
+            let (lowest, _) = self.bounds(idx + 1, k - 1);
+            // values only grow from here, so the remaining target only shrinks below the smallest sum
+            if target - value < lowest {
+                break;
+            }
+            chosen.push(idx);
+            let proceed = self.visit(idx + 1, k - 1, target - value, chosen, emit);
+            chosen.pop();
+            if !proceed {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    // number of sets of `k` distinct indices from `start..` summing to `target`
+    fn count(&self, start: usize, k: usize, target: i128) -> u128 {
+        let len = self.entries.len();
+        if k == 0 {
+            return (target == 0) as u128;
+        }
+        if len - start < k {
+            return 0;
+        }
+        let (lowest, highest) = self.bounds(start, k);
+        if target < lowest || highest < target {
+            return 0;
+        }
+
+        if k == 1 {
+            return self.entries[start..]
+                .iter()
+                .filter(|entry| entry.value as i128 == target)
+                .count() as u128;
+        }
+
+        if k == 2 {
+            let mut total = 0;
+            let (mut lo, mut hi) = (start, len - 1);
+            while lo < hi {
+                let sum = self.entries[lo].value as i128 + self.entries[hi].value as i128;
+                if sum < target {
+                    lo += 1;
+                } else if sum > target {
+                    hi -= 1;
+                } else if self.entries[lo].value == self.entries[hi].value {
+                    let run = (hi - lo + 1) as u128;
+                    total += run * (run - 1) / 2;
+                    break;
+                } else {
+                    let (low_run, high_run) = (self.run_length(lo, true), self.run_length(hi, false));
+                    total += low_run as u128 * high_run as u128;
+                    lo += low_run;
+                    hi -= high_run;
+                }
+            }
+            return total;
+        }
+
+        let mut total = 0;
+        for idx in start..=(len - k) {
+            let value = self.entries[idx].value as i128;
+            let (lowest, _) = self.bounds(idx + 1, k - 1);
+            if target - value < lowest {
+                break;
+            }
+            total += self.count(idx + 1, k - 1, target - value);
+        }
+        total
+    }
+}
+
+fn parse(filename: &Path) -> Result<Vec<i64>, String> {
+    fs::read_to_string(filename)
+        .map_err(|err| format!("Failed to read data for day 01: {}", err))?
+        .split_ascii_whitespace()
+        .map(|token| i64::from_str(token).map_err(|err| format!("Invalid data in file for day 01: {}", err)))
+        .collect()
+}
+
+pub fn ksum(filename: &Path, args: &[String]) -> Result<String, String> {
+    let usage = "Usage: ksum 1 K TARGET [first|all|count]";
+    let (k, target, mode) = match args {
+        [k, target] => (k, target, Mode::First),
+        [k, target, mode] => (k, target, Mode::from_str(mode)?),
+        _ => Err(usage.to_string())?,
+    };
+    let k = usize::from_str(k).map_err(|err| format!("Invalid k '{}': {}", k, err))?;
+    let target = i64::from_str(target).map_err(|err| format!("Invalid target '{}': {}", target, err))?;
+    if k == 0 {
+        Err("k must be at least 1".to_string())?
+    }
+
+    let report = Report::new(&parse(filename)?);
+    let (solutions, count) = report.solve(k, target, mode);
+
+    let mut lines: Vec<String> = solutions
+        .iter()
+        .map(|solution| {
+            format!(
+                "{} (lines {})",
+                solution.describe(),
+                solution
+                    .entries
+                    .iter()
+                    .map(|entry| (entry.index + 1).to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            )
+        })
+        .collect();
+    if mode != Mode::First {
+        lines.push(format!("{}-sums to {}: {}", k, target, count));
+    } else if lines.is_empty() {
+        Err(format!("No {} distinct entries sum to {}", k, target))?
     }
 
-    Err(format!("{:?}", "Failed to find solution for day 01, part 2..."))
+    Ok(lines.join("\n"))
+}
+
+fn solve(filename: &Path, k: usize, part: usize) -> Result<String, String> {
+    let report = Report::new(&parse(filename)?);
+
+    match report.solve(k, 2020, Mode::First).0.first() {
+        Some(solution) => Ok(solution.describe()),
+        None => Err(format!("Failed to find solution for day 01, part {}...", part)),
+    }
+}
+
+pub fn part01(filename: &Path) -> Result<String, String> {
+    solve(filename, 2, 1)
+}
+
+pub fn part02(filename: &Path) -> Result<String, String> {
+    solve(filename, 3, 2)
 }
@@ -1,82 +1,349 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
 use std::fs;
 use std::path::Path;
 use std::str::FromStr;
 
+use lazy_static::lazy_static;
 use regex::Regex;
 
 #[derive(Clone, Debug)]
 struct PasswordData {
-    lower: i32,
-    upper: i32,
-    char: char,
+    line: usize,
+    lower: usize,
+    upper: usize,
+    target: String,
     password: String,
 }
 
+impl fmt::Display for PasswordData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{} {}: {}", self.lower, self.upper, self.target, self.password)
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Rejection {
+    // short category used to summarise rejections over the whole database
+    reason: String,
+    explanation: String,
+}
+
+impl Rejection {
+    fn new(reason: &str, explanation: String) -> Self {
+        Rejection {
+            reason: reason.to_string(),
+            explanation,
+        }
+    }
+}
+
+trait Policy {
+    fn name(&self) -> String;
+    fn check(&self, data: &PasswordData) -> Result<(), Rejection>;
+}
+
+// the target must occur between `lower` and `upper` times, inclusive
+struct CountRange;
+
+impl Policy for CountRange {
+    fn name(&self) -> String {
+        "count".to_string()
+    }
+
+    fn check(&self, data: &PasswordData) -> Result<(), Rejection> {
+        let count = data.password.matches(data.target.as_str()).count();
+        let reason = if count < data.lower {
+            "count: too few"
+        } else if count > data.upper {
+            "count: too many"
+        } else {
+            return Ok(());
+        };
+
+        Err(Rejection::new(
+            reason,
+            format!(
+                "'{}' appears {} time{}, allowed {}-{}",
+                data.target,
+                count,
+                if count == 1 { "" } else { "s" },
+                data.lower,
+                data.upper
+            ),
+        ))
+    }
+}
+
+// the target must start at exactly one of the 1-based character positions `lower` and `upper`
+struct PositionalXor;
+
+// the target-length text at a 1-based character position, shorter (or empty) past the end
+fn text_at(password: &str, position: usize, target: &str) -> String {
+    if position == 0 {
+        String::new()
+    } else {
+        password
+            .chars()
+            .skip(position - 1)
+            .take(target.chars().count())
+            .collect()
+    }
+}
+
+impl Policy for PositionalXor {
+    fn name(&self) -> String {
+        "position".to_string()
+    }
+
+    fn check(&self, data: &PasswordData) -> Result<(), Rejection> {
+        let first = text_at(&data.password, data.lower, &data.target);
+        let second = text_at(&data.password, data.upper, &data.target);
+
+        match (first == data.target, second == data.target) {
+            (true, false) | (false, true) => Ok(()),
+            (true, true) => Err(Rejection::new(
+                "position: both",
+                format!("positions {} and {} are both '{}'", data.lower, data.upper, data.target),
+            )),
+            (false, false) => {
+                let describe = |text: &str| {
+                    if text.is_empty() {
+                        "past the end".to_string()
+                    } else {
+                        format!("'{}'", text)
+                    }
+                };
+                Err(Rejection::new(
+                    "position: neither",
+                    format!(
+                        "neither position {} ({}) nor {} ({}) is '{}'",
+                        data.lower,
+                        describe(&first),
+                        data.upper,
+                        describe(&second),
+                        data.target
+                    ),
+                ))
+            }
+        }
+    }
+}
+
+struct Forbidden(Vec<String>);
+
+impl Policy for Forbidden {
+    fn name(&self) -> String {
+        format!("forbidden={}", self.0.join(","))
+    }
+
+    fn check(&self, data: &PasswordData) -> Result<(), Rejection> {
+        match self
+            .0
+            .iter()
+            .find(|substring| data.password.contains(substring.as_str()))
+        {
+            Some(substring) => Err(Rejection::new(
+                &format!("forbidden: '{}'", substring),
+                format!("contains forbidden '{}'", substring),
+            )),
+            None => Ok(()),
+        }
+    }
+}
+
+struct MinDistinct(usize);
+
+impl Policy for MinDistinct {
+    fn name(&self) -> String {
+        format!("distinct={}", self.0)
+    }
+
+    fn check(&self, data: &PasswordData) -> Result<(), Rejection> {
+        let distinct = data.password.chars().collect::<HashSet<char>>().len();
+        if distinct >= self.0 {
+            Ok(())
+        } else {
+            Err(Rejection::new(
+                "distinct: too few",
+                format!("has {} distinct characters, needs at least {}", distinct, self.0),
+            ))
+        }
+    }
+}
+
+struct Matches(Regex);
+
+impl Policy for Matches {
+    fn name(&self) -> String {
+        format!("regex={}", self.0.as_str())
+    }
+
+    fn check(&self, data: &PasswordData) -> Result<(), Rejection> {
+        if self.0.is_match(&data.password) {
+            Ok(())
+        } else {
+            Err(Rejection::new(
+                "regex: no match",
+                format!("does not match {}", self.0.as_str()),
+            ))
+        }
+    }
+}
+
+fn parse_policy(spec: &str) -> Result<Box<dyn Policy>, String> {
+    let (name, argument) = match spec.find('=') {
+        Some(idx) => (&spec[..idx], Some(&spec[idx + 1..])),
+        None => (spec, None),
+    };
+
+    match (name, argument) {
+        ("count", None) => Ok(Box::new(CountRange)),
+        ("position", None) => Ok(Box::new(PositionalXor)),
+        ("forbidden", Some(substrings)) => Ok(Box::new(Forbidden(
+            substrings
+                .split(',')
+                .filter(|s| !s.is_empty())
+                .map(str::to_string)
+                .collect(),
+        ))),
+        ("distinct", Some(n)) => Ok(Box::new(MinDistinct(
+            usize::from_str(n).map_err(|err| format!("Invalid distinct count '{}': {}", n, err))?,
+        ))),
+        ("regex", Some(pattern)) => Ok(Box::new(Matches(
+            Regex::new(pattern).map_err(|err| format!("Invalid policy regex: {}", err))?,
+        ))),
+        _ => Err(format!(
+            "Unknown policy '{}', expected count, position, forbidden=A,B,.., distinct=N or regex=PATTERN",
+            spec
+        )),
+    }
+}
+
 fn parse(filename: &Path) -> Result<Vec<PasswordData>, String> {
-    let pattern = Regex::new(r"^(\d+)-(\d+)\s+([a-z]):\s+([a-z]+)$").unwrap();
+    lazy_static! {
+        static ref RE: Regex = Regex::new(r"^(\d+)-(\d+)\s+(\S+?):\s(.*)$").unwrap();
+    }
 
     fs::read_to_string(filename)
         .map_err(|err| format!("Failed to read data for day 02: {}", err))?
-        .split("\n")
-        .map(|line| {
-            let captures = pattern.captures(line).ok_or("Failed to parse line in day 2 data")?;
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.is_empty())
+        .map(|(idx, line)| {
+            let captures =
+                RE.captures(line)
+                    .ok_or(format!("Failed to parse line {} in day 2 data: {}", idx + 1, line))?;
 
             Ok(PasswordData {
-                lower: i32::from_str(captures.get(1).unwrap().as_str())
+                line: idx + 1,
+                lower: usize::from_str(captures.get(1).unwrap().as_str())
                     .map_err(|err| format!("Invalid lower bound: {}", err))?,
-                upper: i32::from_str(captures.get(2).unwrap().as_str())
+                upper: usize::from_str(captures.get(2).unwrap().as_str())
                     .map_err(|err| format!("Invalid upper bound: {}", err))?,
-                char: captures.get(3).unwrap().as_str().chars().nth(0).unwrap(),
+                target: captures.get(3).unwrap().as_str().to_string(),
                 password: captures.get(4).unwrap().as_str().to_string(),
             })
         })
         .collect::<Result<Vec<PasswordData>, _>>()
 }
 
-pub fn part01(filename: &Path) -> Result<String, String> {
-    let password_data = parse(filename)?;
+fn count_valid(password_data: &[PasswordData], policies: &[Box<dyn Policy>]) -> usize {
+    password_data
+        .iter()
+        .filter(|data| policies.iter().all(|policy| policy.check(data).is_ok()))
+        .count()
+}
 
-    let valid_passwords = password_data
+fn parse_policies(args: &[String], command: &str) -> Result<Vec<Box<dyn Policy>>, String> {
+    let policies = args
         .iter()
-        .filter(|data| {
-            let len: i32 = data
-                .password
-                .chars()
-                .into_iter()
-                .filter(|c| c == &data.char)
-                .collect::<String>()
-                .len() as i32;
-            data.lower <= len && len <= data.upper
-        })
-        .cloned()
-        .collect::<Vec<_>>()
-        .len();
+        .map(|spec| parse_policy(spec))
+        .collect::<Result<Vec<Box<dyn Policy>>, String>>()?;
+    if policies.is_empty() {
+        Err(format!(
+            "Usage: {} 2 POLICY..., e.g. {} 2 count distinct=4",
+            command, command
+        ))?
+    }
+    Ok(policies)
+}
+
+pub fn explain(filename: &Path, args: &[String]) -> Result<String, String> {
+    let policies = parse_policies(args, "explain")?;
+    let password_data = parse(filename)?;
+
+    let mut lines = Vec::new();
+    let mut reasons: HashMap<String, usize> = HashMap::new();
+    for data in password_data.iter() {
+        let rejections: Vec<Rejection> = policies.iter().filter_map(|policy| policy.check(data).err()).collect();
+        if rejections.is_empty() {
+            continue;
+        }
+
+        for rejection in rejections.iter() {
+            *reasons.entry(rejection.reason.clone()).or_default() += 1;
+        }
+        lines.push(format!(
+            "line {}: {}: {}",
+            data.line,
+            data,
+            rejections
+                .iter()
+                .map(|rejection| rejection.explanation.as_str())
+                .collect::<Vec<&str>>()
+                .join("; ")
+        ));
+    }
+
+    let mut reasons: Vec<(String, usize)> = reasons.into_iter().collect();
+    reasons.sort_by(|(lhs, lhs_count), (rhs, rhs_count)| rhs_count.cmp(lhs_count).then_with(|| lhs.cmp(rhs)));
+
+    lines.push(format!(
+        "Rejected passwords: {} (total: {})",
+        lines.len(),
+        password_data.len()
+    ));
+    lines.extend(
+        reasons
+            .iter()
+            .map(|(reason, count)| format!("    {:>6}  {}", count, reason)),
+    );
+    Ok(lines.join("\n"))
+}
+
+pub fn policy(filename: &Path, args: &[String]) -> Result<String, String> {
+    let policies = parse_policies(args, "policy")?;
+    let password_data = parse(filename)?;
 
     Ok(format!(
-        "Valid passwords: {} (total: {})",
-        valid_passwords,
+        "Valid passwords under {}: {} (total: {})",
+        policies
+            .iter()
+            .map(|policy| policy.name())
+            .collect::<Vec<String>>()
+            .join(" + "),
+        count_valid(&password_data, &policies),
         password_data.len()
     ))
 }
 
-pub fn part02(filename: &Path) -> Result<String, String> {
+pub fn part01(filename: &Path) -> Result<String, String> {
     let password_data = parse(filename)?;
 
-    let valid_passwords = password_data
-        .iter()
-        .filter(|data| {
-            let first = data.password.chars().nth((data.lower - 1) as usize) == Some(data.char);
-            let second = data.password.chars().nth((data.upper - 1) as usize) == Some(data.char);
+    Ok(format!(
+        "Valid passwords: {} (total: {})",
+        count_valid(&password_data, &[Box::new(CountRange)]),
+        password_data.len()
+    ))
+}
 
-            (first || second) && !(first && second)
-        })
-        .cloned()
-        .collect::<Vec<_>>()
-        .len();
+pub fn part02(filename: &Path) -> Result<String, String> {
+    let password_data = parse(filename)?;
 
     Ok(format!(
         "Valid passwords: {} (total: {})",
-        valid_passwords,
+        count_valid(&password_data, &[Box::new(PositionalXor)]),
         password_data.len()
     ))
 }
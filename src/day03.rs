@@ -1,6 +1,12 @@
+use std::collections::HashSet;
 use std::convert::TryFrom;
+use std::fmt;
 use std::fs;
 use std::path::Path;
+use std::str::FromStr;
+
+use num_bigint::BigUint;
+use num_traits::One;
 
 const OPEN: char = '.';
 const TREE: char = '#';
@@ -35,39 +41,243 @@ fn parse(filename: &Path) -> Result<Vec<Vec<Feature>>, String> {
         .collect::<Result<Vec<Vec<Feature>>, _>>()
 }
 
-fn get_feature(landscape: &Vec<Vec<Feature>>, y: usize, x: usize) -> Result<Feature, String> {
-    let row_len = landscape[0].len();
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+struct Slope {
+    // columns moved per step, negative values move left and wrap around the other edge
+    dx: isize,
+    dy: usize,
+}
+
+impl FromStr for Slope {
+    type Err = String;
+
+    // `RIGHT,DOWN`, e.g. `3,1` for right 3, down 1, or `-1,2` for left 1, down 2
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split(',');
+        let (dx, dy) = match (parts.next(), parts.next(), parts.next()) {
+            (Some(dx), Some(dy), None) => (dx.trim(), dy.trim()),
+            _ => Err(format!("Invalid slope '{}', expected RIGHT,DOWN", s))?,
+        };
+
+        let dx = isize::from_str(dx).map_err(|err| format!("Invalid slope '{}': {}", s, err))?;
+        let dy = usize::from_str(dy).map_err(|err| format!("Invalid slope '{}': {}", s, err))?;
+        if dy == 0 {
+            Err(format!("Invalid slope '{}': the toboggan must move down", s))?
+        }
+
+        Ok(Slope { dx, dy })
+    }
+}
+
+impl fmt::Display for Slope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "right {}, down {}", self.dx, self.dy)
+    }
+}
+
+fn get_feature(landscape: &[Vec<Feature>], y: usize, x: isize) -> Result<Feature, String> {
+    let row_len = landscape[0].len() as isize;
     Ok(*landscape
         .get(y)
         .ok_or(&format!("No such landscape row: {}", y))?
-        .get(x % row_len)
-        .ok_or(&format!("No such landscape column: {} ({})", x % row_len, x))?)
+        .get(x.rem_euclid(row_len) as usize)
+        .ok_or(&format!("No such landscape column: {} ({})", x.rem_euclid(row_len), x))?)
 }
 
-fn angled_path_trees(landscape: &Vec<Vec<Feature>>, dy: usize, dx: usize) -> Result<usize, String> {
-    Ok((0..landscape.len())
-        .step_by(dy)
+// visited (row, unwrapped column) positions, starting at the top left
+fn path(landscape: &[Vec<Feature>], slope: Slope) -> Result<Vec<(usize, isize)>, String> {
+    (0..landscape.len())
+        .step_by(slope.dy)
         .enumerate()
-        .map(|(col, row)| get_feature(&landscape, row, col * dx))
+        .map(|(step, row)| {
+            (step as isize)
+                .checked_mul(slope.dx)
+                .map(|col| (row, col))
+                .ok_or(format!(
+                    "Slope {} leaves the representable columns at row {}",
+                    slope, row
+                ))
+        })
+        .collect()
+}
+
+fn angled_path_trees(landscape: &[Vec<Feature>], slope: Slope) -> Result<usize, String> {
+    // only the column within the repeating landscape matters
+    let slope = Slope {
+        dx: slope.dx.rem_euclid(landscape[0].len() as isize),
+        ..slope
+    };
+    Ok(path(landscape, slope)?
+        .into_iter()
+        .map(|(row, col)| get_feature(landscape, row, col))
         .collect::<Result<Vec<Feature>, String>>()?
         .iter()
         .filter(|feat| *feat == &Feature::Tree)
         .count())
 }
 
+// the step with the smallest magnitude that lands on the same columns of a landscape `width` wide
+fn narrowest(dx: isize, width: usize) -> isize {
+    let width = width as isize;
+    let dx = dx.rem_euclid(width);
+    if dx > width / 2 {
+        dx - width
+    } else {
+        dx
+    }
+}
+
+// the landscape repeated sideways far enough to show the whole path, with `O` on open squares and `X` on trees
+fn render(landscape: &[Vec<Feature>], slope: Slope, rows: usize) -> Result<String, String> {
+    let width = landscape[0].len() as isize;
+    let visited: HashSet<(usize, isize)> = path(landscape, slope)?.into_iter().collect();
+
+    let (min_col, max_col) = visited
+        .iter()
+        .filter(|(row, _)| *row < rows)
+        .fold((0, width - 1), |(lo, hi), &(_, col)| (lo.min(col), hi.max(col)));
+    let first = min_col.div_euclid(width) * width;
+    let last = (max_col.div_euclid(width) + 1)
+        .checked_mul(width)
+        .ok_or(format!("Slope {} is too wide to draw", slope))?;
+
+    let mut lines = Vec::new();
+    for row in 0..landscape.len().min(rows) {
+        let mut line = String::new();
+        for col in first..last {
+            let feature = get_feature(landscape, row, col)?;
+            line.push(match (visited.contains(&(row, col)), feature) {
+                (true, Feature::Open) => 'O',
+                (true, Feature::Tree) => 'X',
+                (false, Feature::Open) => OPEN,
+                (false, Feature::Tree) => TREE,
+            });
+        }
+        lines.push(line);
+    }
+
+    Ok(lines.join("\n"))
+}
+
+fn parse_slopes(args: &[String]) -> Result<Vec<Slope>, String> {
+    args.iter().map(|arg| Slope::from_str(arg)).collect()
+}
+
+pub fn slopes(filename: &Path, args: &[String]) -> Result<String, String> {
+    let slopes = parse_slopes(args)?;
+    if slopes.is_empty() {
+        Err("Usage: slopes 3 RIGHT,DOWN..., e.g. slopes 3 3,1 -1,2".to_string())?
+    }
+    let landscape = parse(filename)?;
+
+    let mut lines = Vec::new();
+    let mut product = BigUint::one();
+    for slope in slopes {
+        let trees = angled_path_trees(&landscape, slope)?;
+        product *= trees;
+        lines.push(format!("{:>4} trees: {}", trees, slope));
+    }
+    lines.push(format!("Trees in path product: {}", product));
+
+    Ok(lines.join("\n"))
+}
+
+pub fn search(filename: &Path, args: &[String]) -> Result<String, String> {
+    let usage = "Usage: search 3 fewest|most MAX-RIGHT MAX-DOWN";
+    let (goal, max_dx, max_dy) = match args {
+        [goal, max_dx, max_dy] => (goal.as_str(), max_dx, max_dy),
+        _ => Err(usage.to_string())?,
+    };
+    let max_dx = isize::from_str(max_dx).map_err(|err| format!("Invalid horizontal bound '{}': {}", max_dx, err))?;
+    let max_dy = usize::from_str(max_dy).map_err(|err| format!("Invalid vertical bound '{}': {}", max_dy, err))?;
+    if max_dx < 0 || max_dy == 0 {
+        Err("Slope bounds must allow at least moving straight down".to_string())?
+    }
+    let landscape = parse(filename)?;
+    let width = landscape[0].len();
+
+    // slopes whose steps differ by whole landscape widths meet the same trees, so each class is counted once,
+    // remembering how many slopes within the bounds it stands for
+    let mut counted = Vec::new();
+    for dy in 1..=max_dy {
+        for offset in 0..width {
+            let dx = narrowest(offset as isize, width);
+            let first = -(max_dx as i128) + (offset as i128 + max_dx as i128).rem_euclid(width as i128);
+            if first > max_dx as i128 {
+                continue;
+            }
+            let members = (max_dx as i128 - first) as u128 / width as u128 + 1;
+            let slope = Slope { dx, dy };
+            counted.push((angled_path_trees(&landscape, slope)?, slope, members));
+        }
+    }
+    counted.sort_by_key(|(_, slope, _)| (slope.dy, slope.dx));
+    let best = match goal {
+        "fewest" => counted.iter().map(|(trees, _, _)| *trees).min(),
+        "most" => counted.iter().map(|(trees, _, _)| *trees).max(),
+        _ => Err(usage.to_string())?,
+    }
+    .unwrap();
+
+    let mut lines = vec![format!(
+        "{} trees: {} (out of {} slopes)",
+        if goal == "fewest" { "Fewest" } else { "Most" },
+        best,
+        counted.iter().map(|(_, _, members)| members).sum::<u128>()
+    )];
+    lines.extend(
+        counted
+            .iter()
+            .filter(|(trees, _, _)| *trees == best)
+            .map(|(_, slope, members)| match members {
+                1 => format!("    {}", slope),
+                _ => format!("    {} (plus {} more slopes {} columns apart)", slope, members - 1, width),
+            }),
+    );
+
+    Ok(lines.join("\n"))
+}
+
+pub fn draw(filename: &Path, args: &[String]) -> Result<String, String> {
+    let usage = "Usage: draw 3 RIGHT,DOWN [ROWS]";
+    let (slope, rows) = match args {
+        [slope] => (Slope::from_str(slope)?, usize::MAX),
+        [slope, rows] => (
+            Slope::from_str(slope)?,
+            usize::from_str(rows).map_err(|err| format!("Invalid row count '{}': {}", rows, err))?,
+        ),
+        _ => Err(usage.to_string())?,
+    };
+    let landscape = parse(filename)?;
+
+    // wider steps only repeat the landscape more often, so they are drawn with the narrowest equivalent step
+    let narrow = Slope {
+        dx: narrowest(slope.dx, landscape[0].len()),
+        ..slope
+    };
+    if narrow == slope {
+        render(&landscape, slope, rows)
+    } else {
+        Ok(format!("Slope {} meets the same squares as {}\n{}", slope, narrow, render(&landscape, narrow, rows)?))
+    }
+}
+
 pub fn part01(filename: &Path) -> Result<String, String> {
     let landscape = parse(filename)?;
-    Ok(format!("Trees in path: {}", angled_path_trees(&landscape, 1, 3)?))
+    Ok(format!(
+        "Trees in path: {}",
+        angled_path_trees(&landscape, Slope { dx: 3, dy: 1 })?
+    ))
 }
 
 pub fn part02(filename: &Path) -> Result<String, String> {
     let landscape = parse(filename)?;
 
-    let first = angled_path_trees(&landscape, 1, 1)?;
-    let second = angled_path_trees(&landscape, 1, 3)?;
-    let third = angled_path_trees(&landscape, 1, 5)?;
-    let fourth = angled_path_trees(&landscape, 1, 7)?;
-    let fifth = angled_path_trees(&landscape, 2, 1)?;
+    let first = angled_path_trees(&landscape, Slope { dx: 1, dy: 1 })?;
+    let second = angled_path_trees(&landscape, Slope { dx: 3, dy: 1 })?;
+    let third = angled_path_trees(&landscape, Slope { dx: 5, dy: 1 })?;
+    let fourth = angled_path_trees(&landscape, Slope { dx: 7, dy: 1 })?;
+    let fifth = angled_path_trees(&landscape, Slope { dx: 1, dy: 2 })?;
 
     Ok(format!(
         "Trees in path product: {} * {} * {} * {} * {} = {}",
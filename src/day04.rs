@@ -1,106 +1,493 @@
 use regex::Regex;
+use std::cmp::Ordering;
+use std::fmt;
 use std::fs;
 use std::path::Path;
 use std::str::FromStr;
 
 use lazy_static::lazy_static;
+use num_rational::Ratio;
+use num_traits::Zero;
+use serde_json::json;
 
-#[derive(Clone, Default, Debug)]
-struct Passport {
-    byr: Option<String>,
-    iyr: Option<String>,
-    eyr: Option<String>,
-    hgt: Option<String>,
-    hcl: Option<String>,
-    ecl: Option<String>,
-    pid: Option<String>,
-    cid: Option<String>,
+const PART01_SCHEMA: &str = include_str!("../data/day04-part01.schema");
+const PART02_SCHEMA: &str = include_str!("../data/day04-part02.schema");
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Unit {
+    Centimetre,
+    Inch,
+    Metre,
+    FeetInches,
 }
 
-fn year_in_range(year: &str, min: i32, max: i32) -> bool {
-    i32::from_str(year).map(|year| min <= year && year <= max) == Ok(true)
+impl Unit {
+    // exact length of one unit in micrometres, feet and inches are expressed in inches
+    fn micrometres(&self) -> i64 {
+        match self {
+            Unit::Centimetre => 10_000,
+            Unit::Inch | Unit::FeetInches => 25_400,
+            Unit::Metre => 1_000_000,
+        }
+    }
 }
 
-impl Passport {
-    fn valid_byr(&self) -> bool {
-        self.byr.as_ref().map(|year| year_in_range(year, 1920, 2002)) == Some(true)
+impl FromStr for Unit {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match text {
+            "cm" => Ok(Unit::Centimetre),
+            "in" => Ok(Unit::Inch),
+            "m" => Ok(Unit::Metre),
+            "ft" => Ok(Unit::FeetInches),
+            other => Err(format!("unknown unit '{}'", other)),
+        }
     }
+}
 
-    fn valid_iyr(&self) -> bool {
-        self.iyr.as_ref().map(|year| year_in_range(year, 2010, 2020)) == Some(true)
+impl fmt::Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Unit::Centimetre => write!(f, "cm"),
+            Unit::Inch => write!(f, "in"),
+            Unit::Metre => write!(f, "m"),
+            Unit::FeetInches => write!(f, "ft"),
+        }
     }
+}
+
+// parses a decimal number into an exact integer multiple of 1/`scale`-ths, e.g. ("1.85", 100) => 185
+fn parse_scaled(text: &str, scale: i64) -> Option<i64> {
+    let (whole, fraction) = match text.find('.') {
+        Some(idx) => (&text[..idx], &text[idx + 1..]),
+        None => (text, ""),
+    };
 
-    fn valid_eyr(&self) -> bool {
-        self.eyr.as_ref().map(|year| year_in_range(year, 2020, 2030)) == Some(true)
+    let whole = i64::from_str(whole).ok()?.checked_mul(scale)?;
+    if fraction.is_empty() {
+        return Some(whole);
     }
 
-    fn valid_hgt(&self) -> bool {
-        lazy_static! {
-            static ref RE: Regex = Regex::new(r"^(\d+)(cm|in)$").unwrap();
+    let digits = i64::from_str(fraction).ok()?;
+    let denominator = 10i64.checked_pow(fraction.len() as u32)?;
+    let scaled = digits.checked_mul(scale)?;
+    if scaled % denominator == 0 {
+        whole.checked_add(scaled / denominator)
+    } else {
+        None
+    }
+}
+
+// a length stored exactly in micrometres, remembering the unit it was written in
+#[derive(Copy, Clone, Debug)]
+struct Height {
+    micrometres: i64,
+    unit: Unit,
+}
+
+impl Height {
+    fn value_in(&self, unit: Unit) -> Ratio<i64> {
+        Ratio::new(self.micrometres, unit.micrometres())
+    }
+
+    fn format_in(&self, unit: Unit) -> String {
+        let value = self.value_in(unit);
+        match unit {
+            Unit::FeetInches => {
+                let feet = (value / 12).floor();
+                let inches = value - feet * 12;
+                if inches.is_zero() {
+                    format!("{}'", feet)
+                } else {
+                    format!("{}'{}\"", feet, format_decimal(&inches))
+                }
+            }
+            _ => format!("{}{}", format_decimal(&value), unit),
         }
+    }
 
-        self.hgt.as_ref().map(|hgt| {
-            RE.captures(hgt).map(|groups| {
-                let height = i32::from_str(groups.get(1).unwrap().as_str()).unwrap();
-                let is_cm = groups.get(2).unwrap().as_str() == "cm";
+    // the lower bound of a range ending in `unit`, leaving out a unit suffix the upper bound already shows
+    fn format_bound_in(&self, unit: Unit) -> String {
+        match unit {
+            Unit::FeetInches => self.format_in(unit),
+            _ => format_decimal(&self.value_in(unit)),
+        }
+    }
+}
 
-                (is_cm && 150 <= height && height <= 193) || (!is_cm && 59 <= height && height <= 76)
-            }) == Some(true)
-        }) == Some(true)
+// exact decimal if it terminates within six digits, otherwise rounded to two decimals and marked with ~
+fn format_decimal(value: &Ratio<i64>) -> String {
+    if value.is_integer() {
+        return value.to_integer().to_string();
     }
 
-    fn valid_hcl(&self) -> bool {
+    let scaled = value * 1_000_000;
+    if scaled.is_integer() {
+        format!("{}", *scaled.numer() as f64 / 1_000_000.0)
+    } else {
+        format!("~{:.2}", *value.numer() as f64 / *value.denom() as f64)
+    }
+}
+
+impl FromStr for Height {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
         lazy_static! {
-            static ref RE: Regex = Regex::new(r"^#[0-9a-f]{6}$").unwrap();
+            static ref METRIC: Regex = Regex::new(r"^(\d+(?:\.\d+)?)(cm|in|m)$").unwrap();
+            static ref IMPERIAL: Regex = Regex::new(r#"^(\d+)'(?:(\d+(?:\.\d+)?)")?$"#).unwrap();
+        }
+
+        if let Some(groups) = METRIC.captures(text) {
+            let unit = Unit::from_str(groups.get(2).unwrap().as_str())?;
+            let micrometres = parse_scaled(groups.get(1).unwrap().as_str(), unit.micrometres())
+                .ok_or(format!("'{}' cannot be represented exactly", text))?;
+            Ok(Height { micrometres, unit })
+        } else if let Some(groups) = IMPERIAL.captures(text) {
+            let feet = parse_scaled(groups.get(1).unwrap().as_str(), 12 * Unit::Inch.micrometres());
+            let inches = parse_scaled(groups.get(2).map_or("0", |m| m.as_str()), Unit::Inch.micrometres());
+            let micrometres = feet
+                .zip(inches)
+                .and_then(|(feet, inches)| feet.checked_add(inches))
+                .ok_or(format!("'{}' cannot be represented exactly", text))?;
+            Ok(Height {
+                micrometres,
+                unit: Unit::FeetInches,
+            })
+        } else if text.chars().all(|c| c.is_ascii_digit() || c == '.') && !text.is_empty() {
+            Err("has no unit, expected cm, in, m or ft'in\"".to_string())
+        } else {
+            Err(format!(
+                "'{}' is not a height, expected e.g. 170cm, 67in, 1.7m or 5'7\"",
+                text
+            ))
         }
+    }
+}
+
+impl fmt::Display for Height {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.format_in(self.unit))
+    }
+}
+
+impl PartialEq for Height {
+    fn eq(&self, other: &Self) -> bool {
+        self.micrometres == other.micrometres
+    }
+}
+
+impl Eq for Height {}
+
+impl PartialOrd for Height {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Height {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.micrometres.cmp(&other.micrometres)
+    }
+}
+
+// MIN..=MAX where MIN may leave out the unit of MAX, e.g. 150..=193cm or 150cm..=76in
+fn parse_height_range(text: &str) -> Result<(Height, Height), String> {
+    let idx = text
+        .find("..=")
+        .ok_or(format!("invalid height range '{}', expected MIN..=MAX", text))?;
+    let max = Height::from_str(&text[idx + 3..]).map_err(|err| format!("invalid height range '{}': {}", text, err))?;
+    let min = Height::from_str(&text[..idx])
+        .or_else(|_| Height::from_str(&format!("{}{}", &text[..idx], max.unit)))
+        .map_err(|err| format!("invalid height range '{}': {}", text, err))?;
 
-        self.hcl.as_ref().map(|hcl| RE.is_match(hcl)) == Some(true)
+    Ok((min, max))
+}
+
+#[derive(Clone, Debug)]
+enum Check {
+    Any,
+    Year(i32, i32),
+    Units(Vec<(Height, Height)>),
+    Height(Height, Height),
+    Regex(Regex),
+    Enum(Vec<String>),
+}
+
+fn parse_range(text: &str) -> Option<(i32, i32)> {
+    lazy_static! {
+        static ref RE: Regex = Regex::new(r"^(-?\d+)\.\.=(-?\d+)$").unwrap();
+    }
+
+    let groups = RE.captures(text)?;
+    Some((
+        i32::from_str(groups.get(1).unwrap().as_str()).ok()?,
+        i32::from_str(groups.get(2).unwrap().as_str()).ok()?,
+    ))
+}
+
+impl Check {
+    fn parse(kind: &str, argument: &str) -> Result<Self, String> {
+        match kind {
+            "" | "any" => Ok(Check::Any),
+            "year" => {
+                let (min, max) = parse_range(argument).ok_or(format!("invalid year range '{}'", argument))?;
+                Ok(Check::Year(min, max))
+            }
+            "units" => {
+                let ranges = argument
+                    .split_ascii_whitespace()
+                    .map(parse_height_range)
+                    .collect::<Result<Vec<(Height, Height)>, String>>()?;
+                if ranges.is_empty() {
+                    Err("units needs at least one range".to_string())?
+                }
+                Ok(Check::Units(ranges))
+            }
+            "height" => {
+                let (min, max) = parse_height_range(argument)?;
+                Ok(Check::Height(min, max))
+            }
+            "regex" => Ok(Check::Regex(
+                Regex::new(argument).map_err(|err| format!("invalid regex: {}", err))?,
+            )),
+            "enum" => {
+                let values: Vec<String> = argument.split_ascii_whitespace().map(str::to_string).collect();
+                if values.is_empty() {
+                    Err("enum needs at least one value".to_string())?
+                }
+                Ok(Check::Enum(values))
+            }
+            other => Err(format!("unknown check '{}'", other)),
+        }
     }
 
-    fn valid_ecl(&self) -> bool {
-        self.ecl.as_ref().map(|ecl| {
-            ecl == "amb" || ecl == "blu" || ecl == "brn" || ecl == "gry" || ecl == "grn" || ecl == "hzl" || ecl == "oth"
-        }) == Some(true)
+    fn check(&self, value: &str) -> Result<(), String> {
+        lazy_static! {
+            static ref YEAR: Regex = Regex::new(r"^\d{4}$").unwrap();
+        }
+
+        match self {
+            Check::Any => Ok(()),
+            Check::Year(min, max) => {
+                if !YEAR.is_match(value) {
+                    Err("is not a four digit year".to_string())
+                } else if !(*min..=*max).contains(&i32::from_str(value).unwrap()) {
+                    Err(format!("out of range {}..={}", min, max))
+                } else {
+                    Ok(())
+                }
+            }
+            Check::Units(ranges) => {
+                let height = Height::from_str(value)?;
+                let (min, max) = ranges.iter().find(|(_, max)| max.unit == height.unit).ok_or(format!(
+                    "has unit {}, expected {}",
+                    height.unit,
+                    ranges
+                        .iter()
+                        .map(|(_, max)| max.unit.to_string())
+                        .collect::<Vec<String>>()
+                        .join(" or ")
+                ))?;
+
+                if (min..=max).contains(&&height) {
+                    Ok(())
+                } else {
+                    Err(format!("out of range {}..={}", min.format_bound_in(max.unit), max))
+                }
+            }
+            Check::Height(min, max) => {
+                let height = Height::from_str(value)?;
+                if (min..=max).contains(&&height) {
+                    Ok(())
+                } else {
+                    Err(format!("out of range {}..={}", min, max))
+                }
+            }
+            Check::Regex(re) => {
+                if re.is_match(value) {
+                    Ok(())
+                } else {
+                    Err(format!("does not match {}", re.as_str()))
+                }
+            }
+            Check::Enum(values) => {
+                if values.iter().any(|v| v == value) {
+                    Ok(())
+                } else {
+                    Err("not in enum".to_string())
+                }
+            }
+        }
     }
+}
+
+#[derive(Clone, Debug)]
+struct FieldRule {
+    name: String,
+    required: bool,
+    check: Check,
+}
+
+#[derive(Clone, Debug)]
+struct Schema {
+    fields: Vec<FieldRule>,
+}
+
+impl FromStr for Schema {
+    type Err = String;
 
-    fn valid_pid(&self) -> bool {
+    // one field per line: NAME required|optional [CHECK [ARGUMENT]], lines starting with # are comments
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
         lazy_static! {
-            static ref RE: Regex = Regex::new(r"^[0-9]{9}$").unwrap();
+            static ref RE: Regex = Regex::new(r"^(\S+)\s+(required|optional)(?:\s+(\S+)(?:\s+(.*))?)?$").unwrap();
+        }
+
+        let mut fields: Vec<FieldRule> = Vec::new();
+        for (idx, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let groups = RE.captures(line).ok_or(format!(
+                "Invalid schema line {}: expected 'NAME required|optional [CHECK ARGUMENT]'",
+                idx + 1
+            ))?;
+            let name = groups.get(1).unwrap().as_str();
+            if fields.iter().any(|field| field.name == name) {
+                Err(format!("Invalid schema line {}: duplicate field '{}'", idx + 1, name))?
+            }
+
+            let check = Check::parse(
+                groups.get(3).map_or("", |m| m.as_str()),
+                groups.get(4).map_or("", |m| m.as_str().trim()),
+            )
+            .map_err(|err| format!("Invalid schema line {}: {}", idx + 1, err))?;
+
+            fields.push(FieldRule {
+                name: name.to_string(),
+                required: groups.get(2).unwrap().as_str() == "required",
+                check,
+            });
+        }
+
+        Ok(Schema { fields })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Violation {
+    Missing(String),
+    Invalid(String, String, String),
+    Duplicate(String),
+    Unknown(String, String),
+}
+
+impl Violation {
+    // unknown fields are reported, but do not make a passport invalid
+    fn is_error(&self) -> bool {
+        !matches!(self, Violation::Unknown(_, _))
+    }
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Violation::Missing(field) => write!(f, "{} missing", field),
+            Violation::Invalid(field, value, reason) => write!(f, "{}={} {}", field, value, reason),
+            Violation::Duplicate(field) => write!(f, "{} given more than once", field),
+            Violation::Unknown(field, value) => write!(f, "{}={} unknown field", field, value),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+struct Passport {
+    line: usize,
+    fields: Vec<(String, String)>,
+}
+
+impl Passport {
+    fn get(&self, name: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+impl Schema {
+    fn validate(&self, passport: &Passport) -> Vec<Violation> {
+        let mut violations = Vec::new();
+
+        for rule in self.fields.iter() {
+            match passport.get(&rule.name) {
+                None if rule.required => violations.push(Violation::Missing(rule.name.clone())),
+                None => (),
+                Some(value) => {
+                    if let Err(reason) = rule.check.check(value) {
+                        violations.push(Violation::Invalid(rule.name.clone(), value.to_string(), reason));
+                    }
+                }
+            }
         }
 
-        self.pid.as_ref().map(|pid| RE.is_match(pid)) == Some(true)
+        for (idx, (key, value)) in passport.fields.iter().enumerate() {
+            if passport.fields[..idx].iter().any(|(other, _)| other == key) {
+                violations.push(Violation::Duplicate(key.clone()));
+            } else if !self.fields.iter().any(|rule| &rule.name == key) {
+                violations.push(Violation::Unknown(key.clone(), value.clone()));
+            }
+        }
+
+        violations
+    }
+
+    fn is_valid(&self, passport: &Passport) -> bool {
+        self.validate(passport).iter().all(|violation| !violation.is_error())
     }
 }
 
+fn load_schema(filename: &Path) -> Result<Schema, String> {
+    Schema::from_str(
+        &fs::read_to_string(filename)
+            .map_err(|err| format!("Failed to read schema {}: {}", filename.display(), err))?,
+    )
+}
+
 fn parse(filename: &Path) -> Result<Vec<Passport>, String> {
     lazy_static! {
-        static ref RE: Regex = Regex::new(r"^([a-z]{3}):(.*)$").unwrap();
+        static ref RE: Regex = Regex::new(r"^([^:\s]+):(.*)$").unwrap();
     }
 
+    let mut line = 1;
     fs::read_to_string(filename)
-        .map_err(|err| format!("Failed to read data for day 03: {}", err))?
+        .map_err(|err| format!("Failed to read data for day 04: {}", err))?
         .split("\n\n")
         .map(|text| {
-            let mut passport = Passport::default();
+            let start = line;
+            line += text.matches('\n').count() + 2;
+            (start, text)
+        })
+        .filter(|(_, text)| !text.trim().is_empty())
+        .map(|(line, text)| {
+            // records may start with blank lines when separated by more than one empty line
+            let line = line + text.len() - text.trim_start_matches('\n').len();
+            let mut passport = Passport {
+                line,
+                fields: Vec::new(),
+            };
 
             for field in text.split_ascii_whitespace() {
                 let fields = RE
                     .captures(field)
                     .ok_or(format!("Failed to parse passport field: {}", field))?;
-                let key = fields.get(1).unwrap().as_str();
-                let value = fields.get(2).unwrap().as_str();
-
-                match key {
-                    "byr" => passport.byr = Some(value.to_string()),
-                    "iyr" => passport.iyr = Some(value.to_string()),
-                    "eyr" => passport.eyr = Some(value.to_string()),
-                    "hgt" => passport.hgt = Some(value.to_string()),
-                    "hcl" => passport.hcl = Some(value.to_string()),
-                    "ecl" => passport.ecl = Some(value.to_string()),
-                    "pid" => passport.pid = Some(value.to_string()),
-                    "cid" => passport.cid = Some(value.to_string()),
-                    _ => unreachable!(), // don't handle since the data doesn't include extra fields
-                }
+                passport.fields.push((
+                    fields.get(1).unwrap().as_str().to_string(),
+                    fields.get(2).unwrap().as_str().to_string(),
+                ));
             }
 
             Ok(passport)
@@ -108,42 +495,153 @@ fn parse(filename: &Path) -> Result<Vec<Passport>, String> {
         .collect::<Result<Vec<Passport>, String>>()
 }
 
+fn count_valid(schema: &Schema, data: &[Passport]) -> usize {
+    data.iter().filter(|passport| schema.is_valid(passport)).count()
+}
+
+fn report_text(schema: &Schema, data: &[Passport]) -> String {
+    let mut lines = Vec::new();
+    for (idx, passport) in data.iter().enumerate() {
+        let violations = schema.validate(passport);
+        let valid = violations.iter().all(|violation| !violation.is_error());
+
+        lines.push(format!(
+            "passport {} (line {}): {}",
+            idx + 1,
+            passport.line,
+            if valid { "valid" } else { "invalid" }
+        ));
+        lines.extend(violations.iter().map(|violation| format!("    {}", violation)));
+    }
+
+    lines.push(format!(
+        "Valid passports {} (total: {})",
+        count_valid(schema, data),
+        data.len()
+    ));
+    lines.join("\n")
+}
+
+fn report_json(schema: &Schema, data: &[Passport]) -> String {
+    let passports: Vec<serde_json::Value> = data
+        .iter()
+        .enumerate()
+        .map(|(idx, passport)| {
+            let violations = schema.validate(passport);
+            let mut missing = Vec::new();
+            let mut invalid = Vec::new();
+            let mut duplicate = Vec::new();
+            let mut unknown = Vec::new();
+
+            for violation in violations.iter() {
+                match violation {
+                    Violation::Missing(field) => missing.push(json!(field)),
+                    Violation::Invalid(field, value, reason) => {
+                        invalid.push(json!({"field": field, "value": value, "reason": reason}))
+                    }
+                    Violation::Duplicate(field) => duplicate.push(json!(field)),
+                    Violation::Unknown(field, value) => unknown.push(json!({"field": field, "value": value})),
+                }
+            }
+
+            json!({
+                "record": idx + 1,
+                "line": passport.line,
+                "valid": violations.iter().all(|violation| !violation.is_error()),
+                "missing": missing,
+                "invalid": invalid,
+                "duplicate": duplicate,
+                "unknown": unknown,
+            })
+        })
+        .collect();
+
+    serde_json::to_string_pretty(&json!({
+        "valid": count_valid(schema, data),
+        "total": data.len(),
+        "passports": passports,
+    }))
+    .unwrap()
+}
+
+pub fn report(filename: &Path, args: &[String]) -> Result<String, String> {
+    let schema = match args.iter().find(|arg| !arg.starts_with("--")) {
+        Some(schema) => load_schema(Path::new(schema))?,
+        None => Schema::from_str(PART02_SCHEMA)?,
+    };
+    let data = parse(filename)?;
+
+    if args.iter().any(|arg| arg == "--json") {
+        Ok(report_json(&schema, &data))
+    } else {
+        Ok(report_text(&schema, &data))
+    }
+}
+
+pub fn heights(filename: &Path, args: &[String]) -> Result<String, String> {
+    let (min, max) = match args {
+        [min, max] => (Height::from_str(min)?, Height::from_str(max)?),
+        _ => Err("Usage: heights 4 MIN MAX, e.g. heights 4 150cm 76in".to_string())?,
+    };
+    let data = parse(filename)?;
+
+    let mut lines = Vec::new();
+    for (idx, passport) in data.iter().enumerate() {
+        if let Some(Ok(height)) = passport.get("hgt").map(Height::from_str) {
+            if min <= height && height <= max {
+                lines.push(format!(
+                    "passport {} (line {}): hgt={} ({} / {})",
+                    idx + 1,
+                    passport.line,
+                    height,
+                    height.format_in(Unit::Centimetre),
+                    height.format_in(Unit::Inch)
+                ));
+            }
+        }
+    }
+
+    lines.push(format!(
+        "Passports with height between {} and {}: {} (total: {})",
+        min,
+        max,
+        lines.len(),
+        data.len()
+    ));
+    Ok(lines.join("\n"))
+}
+
+pub fn validate(filename: &Path, args: &[String]) -> Result<String, String> {
+    let schema = load_schema(Path::new(
+        args.first().ok_or("Usage: validate 4 SCHEMA-FILE".to_string())?,
+    ))?;
+    let data = parse(filename)?;
+
+    Ok(format!(
+        "Valid passports {} (total: {})",
+        count_valid(&schema, &data),
+        data.len()
+    ))
+}
+
 pub fn part01(filename: &Path) -> Result<String, String> {
+    let schema = Schema::from_str(PART01_SCHEMA)?;
     let data = parse(filename)?;
 
     Ok(format!(
         "Valid passports {} (total: {})",
-        data.iter()
-            .filter(|passport| {
-                passport.byr.is_some()
-                    && passport.iyr.is_some()
-                    && passport.eyr.is_some()
-                    && passport.hgt.is_some()
-                    && passport.hcl.is_some()
-                    && passport.ecl.is_some()
-                    && passport.pid.is_some()
-            })
-            .count(),
+        count_valid(&schema, &data),
         data.len()
     ))
 }
 
 pub fn part02(filename: &Path) -> Result<String, String> {
+    let schema = Schema::from_str(PART02_SCHEMA)?;
     let data = parse(filename)?;
 
     Ok(format!(
         "Valid passports {} (total: {})",
-        data.iter()
-            .filter(|passport| {
-                passport.valid_byr()
-                    && passport.valid_iyr()
-                    && passport.valid_eyr()
-                    && passport.valid_hgt()
-                    && passport.valid_hcl()
-                    && passport.valid_ecl()
-                    && passport.valid_pid()
-            })
-            .count(),
+        count_valid(&schema, &data),
         data.len()
     ))
 }
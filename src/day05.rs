@@ -1,49 +1,278 @@
 use std::collections::BTreeSet;
+use std::fmt;
 use std::fs;
 use std::iter::FromIterator;
 use std::path::Path;
+use std::str::FromStr;
 
-fn parse_seat_code(code: &str) -> i32 {
-    i32::from_str_radix(
-        &code
-            .replace("F", "0")
-            .replace("L", "0")
-            .replace("B", "1")
-            .replace("R", "1"),
-        2,
-    )
-    .unwrap()
+// number of F/B characters encoding the row and L/R characters encoding the column
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+struct Geometry {
+    row_bits: u32,
+    column_bits: u32,
 }
 
-pub fn part01(filename: &Path) -> Result<String, String> {
-    Ok(format!(
-        "Maximum seat ID: {}",
-        fs::read_to_string(filename)
-            .map_err(|err| format!("Failed to read data for day 05: {}", err))?
-            .split_ascii_whitespace()
-            .map(parse_seat_code)
-            .max()
-            .unwrap()
-    ))
+impl Default for Geometry {
+    fn default() -> Self {
+        Geometry {
+            row_bits: 7,
+            column_bits: 3,
+        }
+    }
 }
 
-pub fn part02(filename: &Path) -> Result<String, String> {
-    let seats: BTreeSet<i32> = fs::read_to_string(filename)
+impl Geometry {
+    fn rows(&self) -> u64 {
+        1 << self.row_bits
+    }
+
+    fn columns(&self) -> u64 {
+        1 << self.column_bits
+    }
+
+    fn seats(&self) -> u64 {
+        self.rows() * self.columns()
+    }
+}
+
+impl FromStr for Geometry {
+    type Err = String;
+
+    // `ROW-BITS,COLUMN-BITS`, e.g. `7,3` for the puzzle's plane
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bits = s
+            .split(',')
+            .map(|part| u32::from_str(part.trim()).map_err(|err| format!("Invalid geometry '{}': {}", s, err)))
+            .collect::<Result<Vec<u32>, String>>()?;
+
+        match bits[..] {
+            [row_bits, column_bits] if row_bits.checked_add(column_bits) == Some(0) => {
+                Err(format!("Invalid geometry '{}': the plane needs at least one seat", s))
+            }
+            [row_bits, column_bits] if !matches!(row_bits.checked_add(column_bits), Some(bits) if bits <= 32) => {
+                Err(format!("Invalid geometry '{}': at most 32 bits are supported", s))
+            }
+            [row_bits, column_bits] => Ok(Geometry { row_bits, column_bits }),
+            _ => Err(format!("Invalid geometry '{}', expected ROW-BITS,COLUMN-BITS", s)),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+struct BoardingPass {
+    geometry: Geometry,
+    row: u64,
+    column: u64,
+}
+
+impl BoardingPass {
+    fn decode(code: &str, geometry: Geometry) -> Result<Self, String> {
+        let chars: Vec<char> = code.chars().collect();
+        let expected = (geometry.row_bits + geometry.column_bits) as usize;
+        if chars.len() != expected {
+            Err(format!(
+                "Boarding pass '{}' has {} characters, expected {}",
+                code,
+                chars.len(),
+                expected
+            ))?
+        }
+
+        let (row_part, column_part) = chars.split_at(geometry.row_bits as usize);
+        let decode_part = |part: &[char], offset: usize, low: char, high: char| {
+            part.iter().enumerate().try_fold(0, |acc, (idx, &c)| match c {
+                _ if c == low => Ok(acc << 1),
+                _ if c == high => Ok(acc << 1 | 1),
+                _ => Err(format!(
+                    "Boarding pass '{}' has '{}' at position {}, expected '{}' or '{}'",
+                    code,
+                    c,
+                    offset + idx + 1,
+                    low,
+                    high
+                )),
+            })
+        };
+
+        Ok(BoardingPass {
+            geometry,
+            row: decode_part(row_part, 0, 'F', 'B')?,
+            column: decode_part(column_part, row_part.len(), 'L', 'R')?,
+        })
+    }
+
+    fn from_seat_id(seat_id: u64, geometry: Geometry) -> Result<Self, String> {
+        if seat_id >= geometry.seats() {
+            Err(format!(
+                "Seat ID {} is outside the plane, the highest seat ID is {}",
+                seat_id,
+                geometry.seats() - 1
+            ))?
+        }
+
+        Ok(BoardingPass {
+            geometry,
+            row: seat_id >> geometry.column_bits,
+            column: seat_id & (geometry.columns() - 1),
+        })
+    }
+
+    fn seat_id(&self) -> u64 {
+        self.row << self.geometry.column_bits | self.column
+    }
+
+    fn encode(&self) -> String {
+        let encode_part = |value: u64, bits: u32, low: char, high: char| {
+            (0..bits)
+                .rev()
+                .map(move |bit| if value >> bit & 1 == 1 { high } else { low })
+        };
+
+        encode_part(self.row, self.geometry.row_bits, 'F', 'B')
+            .chain(encode_part(self.column, self.geometry.column_bits, 'L', 'R'))
+            .collect()
+    }
+}
+
+impl fmt::Display for BoardingPass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.encode())
+    }
+}
+
+fn parse(filename: &Path, geometry: Geometry) -> Result<Vec<BoardingPass>, String> {
+    fs::read_to_string(filename)
         .map_err(|err| format!("Failed to read data for day 05: {}", err))?
-        .split_ascii_whitespace()
-        .map(parse_seat_code)
-        .collect();
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(idx, line)| {
+            BoardingPass::decode(line.trim(), geometry)
+                .map_err(|err| format!("Failed to parse line {} in day 5 data: {}", idx + 1, err))
+        })
+        .collect()
+}
 
-    let possible_seats: Vec<i32> = BTreeSet::from_iter(*seats.iter().min().unwrap()..*seats.iter().max().unwrap())
-        .difference(&seats)
-        .cloned()
-        .collect();
+// the free seat with occupied seats on both sides
+fn find_your_seat(seats: &BTreeSet<u64>) -> Result<u64, String> {
+    let (min, max) = match (seats.iter().next(), seats.iter().next_back()) {
+        (Some(&min), Some(&max)) => (min, max),
+        _ => Err("There are no boarding passes".to_string())?,
+    };
+
+    let possible_seats: Vec<u64> = BTreeSet::from_iter(min..max).difference(seats).cloned().collect();
 
     if possible_seats.len() == 1 {
-        Ok(format!("Your seat ID: {}", possible_seats.first().unwrap()))
+        Ok(possible_seats[0])
     } else if possible_seats.is_empty() {
         Err("There are no possible seats".to_string())
     } else {
         Err("There are multiple possible seats".to_string())
     }
 }
+
+// an optional leading `geometry=ROW-BITS,COLUMN-BITS` argument followed by the remaining arguments
+fn split_geometry(args: &[String]) -> Result<(Geometry, &[String]), String> {
+    match args.first() {
+        Some(arg) if arg.starts_with("geometry=") => Ok((Geometry::from_str(&arg["geometry=".len()..])?, &args[1..])),
+        _ => Ok((Geometry::default(), args)),
+    }
+}
+
+pub fn decode(_: &Path, args: &[String]) -> Result<String, String> {
+    let (geometry, codes) = split_geometry(args)?;
+    if codes.is_empty() {
+        Err("Usage: decode 5 [geometry=ROW-BITS,COLUMN-BITS] CODE...".to_string())?
+    }
+
+    Ok(codes
+        .iter()
+        .map(|code| {
+            let pass = BoardingPass::decode(code, geometry)?;
+            Ok(format!(
+                "{}: row {}, column {}, seat ID {}",
+                pass,
+                pass.row,
+                pass.column,
+                pass.seat_id()
+            ))
+        })
+        .collect::<Result<Vec<String>, String>>()?
+        .join("\n"))
+}
+
+pub fn encode(_: &Path, args: &[String]) -> Result<String, String> {
+    let (geometry, seat_ids) = split_geometry(args)?;
+    if seat_ids.is_empty() {
+        Err("Usage: encode 5 [geometry=ROW-BITS,COLUMN-BITS] SEAT-ID...".to_string())?
+    }
+
+    Ok(seat_ids
+        .iter()
+        .map(|seat_id| {
+            let seat_id = u64::from_str(seat_id).map_err(|err| format!("Invalid seat ID '{}': {}", seat_id, err))?;
+            Ok(format!(
+                "{}: {}",
+                seat_id,
+                BoardingPass::from_seat_id(seat_id, geometry)?
+            ))
+        })
+        .collect::<Result<Vec<String>, String>>()?
+        .join("\n"))
+}
+
+// one line per row, `#` for occupied seats, `.` for free seats and `@` for your seat
+pub fn seatmap(filename: &Path, args: &[String]) -> Result<String, String> {
+    let (geometry, rest) = split_geometry(args)?;
+    if !rest.is_empty() {
+        Err("Usage: seatmap 5 [geometry=ROW-BITS,COLUMN-BITS]".to_string())?
+    }
+    let seats: BTreeSet<u64> = parse(filename, geometry)?.iter().map(BoardingPass::seat_id).collect();
+    let yours = find_your_seat(&seats).ok();
+
+    let width = (geometry.rows() - 1).to_string().len();
+    let mut lines = Vec::new();
+    for row in 0..geometry.rows() {
+        let line: String = (0..geometry.columns())
+            .map(|column| {
+                let seat_id = row << geometry.column_bits | column;
+                if Some(seat_id) == yours {
+                    '@'
+                } else if seats.contains(&seat_id) {
+                    '#'
+                } else {
+                    '.'
+                }
+            })
+            .collect();
+        lines.push(format!("{:>width$} {}", row, line, width = width));
+    }
+    lines.push(format!(
+        "Occupied: {}, free: {}, your seat: {}",
+        seats.len(),
+        geometry.seats() - seats.len() as u64,
+        yours.map_or("unknown".to_string(), |seat_id| seat_id.to_string())
+    ));
+
+    Ok(lines.join("\n"))
+}
+
+pub fn part01(filename: &Path) -> Result<String, String> {
+    Ok(format!(
+        "Maximum seat ID: {}",
+        parse(filename, Geometry::default())?
+            .iter()
+            .map(BoardingPass::seat_id)
+            .max()
+            .ok_or("There are no boarding passes")?
+    ))
+}
+
+pub fn part02(filename: &Path) -> Result<String, String> {
+    let seats: BTreeSet<u64> = parse(filename, Geometry::default())?
+        .iter()
+        .map(BoardingPass::seat_id)
+        .collect();
+
+    Ok(format!("Your seat ID: {}", find_your_seat(&seats)?))
+}
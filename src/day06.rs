@@ -1,42 +1,199 @@
-use std::collections::HashSet;
 use std::fs;
 use std::path::Path;
+use std::str::FromStr;
 
-pub fn part01(filename: &Path) -> Result<String, String> {
-    let yes_answers: usize = fs::read_to_string(filename)
-        .map_err(|err| format!("Failed to read data for day 06: {}", err))?
-        .split("\n\n")
-        .map(|answers| {
-            answers
-                .lines()
-                .map(|line| line.chars())
-                .flatten()
-                .collect::<HashSet<char>>()
-                .len()
-        })
-        .sum();
+const QUESTIONS: usize = 26;
+
+// one bit per question `a` to `z`
+type Answers = u32;
 
-    Ok(format!("Sum of any YES answers: {}", yes_answers))
+fn question_bit(question: char) -> Option<Answers> {
+    if question.is_ascii_lowercase() {
+        Some(1 << (question as u8 - b'a'))
+    } else {
+        None
+    }
 }
 
-pub fn part02(filename: &Path) -> Result<String, String> {
-    let yes_answers: usize = fs::read_to_string(filename)
+fn question_char(idx: usize) -> char {
+    (b'a' + idx as u8) as char
+}
+
+struct Group {
+    people: Vec<Answers>,
+}
+
+impl Group {
+    fn anyone(&self) -> Answers {
+        self.people.iter().fold(0, |acc, &person| acc | person)
+    }
+
+    fn everyone(&self) -> Answers {
+        self.people
+            .iter()
+            .fold(!0 >> (32 - QUESTIONS), |acc, &person| acc & person)
+    }
+
+    // number of people answering yes to each question
+    fn counts(&self) -> [usize; QUESTIONS] {
+        let mut counts = [0; QUESTIONS];
+        for person in self.people.iter() {
+            for (idx, count) in counts.iter_mut().enumerate() {
+                *count += (person >> idx & 1) as usize;
+            }
+        }
+        counts
+    }
+
+    fn answered_by(&self, predicate: impl Fn(usize) -> bool) -> Answers {
+        self.counts()
+            .iter()
+            .enumerate()
+            .filter(|(_, &count)| predicate(count))
+            .fold(0, |acc, (idx, _)| acc | 1 << idx)
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+enum Query {
+    Anyone,
+    Everyone,
+    Exactly(usize),
+    Majority,
+    Nobody,
+    Histogram,
+}
+
+impl FromStr for Query {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "anyone" => Ok(Query::Anyone),
+            "everyone" => Ok(Query::Everyone),
+            "majority" => Ok(Query::Majority),
+            "nobody" => Ok(Query::Nobody),
+            "histogram" => Ok(Query::Histogram),
+            _ if s.starts_with("exactly=") => Ok(Query::Exactly(
+                usize::from_str(&s["exactly=".len()..]).map_err(|err| format!("Invalid query '{}': {}", s, err))?,
+            )),
+            _ => Err(format!(
+                "Unknown query '{}', expected anyone, everyone, exactly=K, majority, nobody or histogram",
+                s
+            )),
+        }
+    }
+}
+
+impl Query {
+    fn describe(&self) -> String {
+        match self {
+            Query::Anyone => "any".to_string(),
+            Query::Everyone => "all".to_string(),
+            Query::Exactly(k) => format!("exactly {}", k),
+            Query::Majority => "majority".to_string(),
+            Query::Nobody => "no".to_string(),
+            Query::Histogram => "histogram".to_string(),
+        }
+    }
+
+    fn select(&self, group: &Group) -> Answers {
+        match self {
+            Query::Anyone => group.anyone(),
+            Query::Everyone => group.everyone(),
+            Query::Exactly(k) => group.answered_by(|count| count == *k),
+            Query::Majority => group.answered_by(|count| 2 * count > group.people.len()),
+            Query::Nobody => !group.anyone() & !0 >> (32 - QUESTIONS),
+            Query::Histogram => unreachable!("histograms are not a per-group selection"),
+        }
+    }
+
+    fn evaluate(&self, groups: &[Group]) -> String {
+        match self {
+            Query::Histogram => histogram(groups),
+            _ => format!(
+                "Sum of {} YES answers: {}",
+                self.describe(),
+                groups
+                    .iter()
+                    .map(|group| self.select(group).count_ones() as usize)
+                    .sum::<usize>()
+            ),
+        }
+    }
+}
+
+// per question: people answering yes over all groups, groups where anyone did, and a bar
+fn histogram(groups: &[Group]) -> String {
+    let mut people = [0; QUESTIONS];
+    let mut in_groups = [0; QUESTIONS];
+    for group in groups.iter() {
+        for (idx, count) in group.counts().iter().enumerate() {
+            people[idx] += count;
+            in_groups[idx] += (*count > 0) as usize;
+        }
+    }
+
+    let most = people.iter().cloned().max().unwrap_or(0).max(1);
+    let mut lines = vec!["question  people  groups".to_string()];
+    lines.extend((0..QUESTIONS).map(|idx| {
+        format!(
+            "{:>8}  {:>6}  {:>6}  {}",
+            question_char(idx),
+            people[idx],
+            in_groups[idx],
+            "#".repeat(people[idx] * 40 / most)
+        )
+    }));
+    lines.join("\n")
+}
+
+fn parse(filename: &Path) -> Result<Vec<Group>, String> {
+    fs::read_to_string(filename)
         .map_err(|err| format!("Failed to read data for day 06: {}", err))?
         .split("\n\n")
-        .map(|answers| {
-            answers
-                .lines()
-                .map(|line| line.chars().collect::<HashSet<char>>())
-                .fold::<Option<HashSet<char>>, _>(None, |acc, other| {
-                    Some(match &acc {
-                        Some(map) => map.intersection(&other).cloned().collect(),
-                        None => other,
+        .filter(|answers| !answers.trim().is_empty())
+        .enumerate()
+        .map(|(idx, answers)| {
+            Ok(Group {
+                people: answers
+                    .lines()
+                    .map(|line| {
+                        line.chars().try_fold(0, |acc, question| {
+                            question_bit(question).map(|bit| acc | bit).ok_or(format!(
+                                "Invalid question '{}' in group {} of day 6 data",
+                                question,
+                                idx + 1
+                            ))
+                        })
                     })
-                })
-                .unwrap()
-                .len()
+                    .collect::<Result<Vec<Answers>, String>>()?,
+            })
         })
-        .sum();
+        .collect()
+}
+
+pub fn query(filename: &Path, args: &[String]) -> Result<String, String> {
+    let queries = args
+        .iter()
+        .map(|arg| Query::from_str(arg))
+        .collect::<Result<Vec<Query>, String>>()?;
+    if queries.is_empty() {
+        Err("Usage: query 6 QUERY..., e.g. query 6 exactly=2 majority nobody histogram".to_string())?
+    }
+    let groups = parse(filename)?;
+
+    Ok(queries
+        .iter()
+        .map(|query| query.evaluate(&groups))
+        .collect::<Vec<String>>()
+        .join("\n"))
+}
 
-    Ok(format!("Sum of all YES answers: {}", yes_answers))
+pub fn part01(filename: &Path) -> Result<String, String> {
+    Ok(Query::Anyone.evaluate(&parse(filename)?))
+}
+
+pub fn part02(filename: &Path) -> Result<String, String> {
+    Ok(Query::Everyone.evaluate(&parse(filename)?))
 }
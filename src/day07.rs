@@ -1,103 +1,495 @@
 use regex::Regex;
-use std::collections::{HashMap, HashSet};
-use std::convert::TryInto;
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::fs;
 use std::path::Path;
 use std::str::FromStr;
 
-type Graph = HashMap<String, Vec<(String, i32)>>;
+use lazy_static::lazy_static;
+use num_bigint::BigUint;
+use num_traits::Zero;
+use serde_json::json;
 
-fn parse(filename: &Path) -> Result<Graph, String> {
-    let re = Regex::new(r"^(\d+)\s+(.*)\s+bags?\.?$").unwrap();
+#[derive(Clone, Debug, Default)]
+struct Graph {
+    colours: Vec<String>,
+    index: HashMap<String, usize>,
+    edges: Vec<Vec<(usize, u32)>>,
+}
 
-    Ok(fs::read_to_string(filename)
-        .map_err(|err| format!("Failed to read data for day 07: {}", err))?
-        .lines()
-        .map(|line| {
-            let [bag, contents]: [&str; 2] = line.split(" bags contain ").collect::<Vec<&str>>().try_into().unwrap();
-            (
-                bag.to_owned(),
-                if contents != "no other bags." {
-                    contents
-                        .split(", ")
-                        .map(|token| {
-                            let fields = re.captures(token).unwrap();
-                            (
-                                /* inner bag */ fields.get(2).unwrap().as_str().to_owned(),
-                                /* count */ i32::from_str(fields.get(1).unwrap().as_str()).unwrap(),
-                            )
-                        })
-                        .collect::<Vec<(String, i32)>>()
+impl Graph {
+    fn intern(&mut self, colour: &str) -> usize {
+        if let Some(&id) = self.index.get(colour) {
+            return id;
+        }
+
+        let id = self.colours.len();
+        self.colours.push(colour.to_owned());
+        self.index.insert(colour.to_owned(), id);
+        self.edges.push(Vec::new());
+        id
+    }
+
+    fn id(&self, colour: &str) -> Result<usize, String> {
+        self.index
+            .get(colour)
+            .copied()
+            .ok_or(format!("No rule for '{}' bags", colour))
+    }
+
+    fn len(&self) -> usize {
+        self.colours.len()
+    }
+
+    fn reverse(&self) -> Graph {
+        let mut reversed = Graph {
+            colours: self.colours.clone(),
+            index: self.index.clone(),
+            edges: vec![Vec::new(); self.len()],
+        };
+
+        for (node, edges) in self.edges.iter().enumerate() {
+            for &(next, count) in edges.iter() {
+                reversed.edges[next].push((node, count));
+            }
+        }
+
+        reversed
+    }
+
+    fn format_path(&self, path: &[usize]) -> String {
+        path.iter()
+            .map(|&id| self.colours[id].as_str())
+            .collect::<Vec<&str>>()
+            .join(" -> ")
+    }
+
+    // topological order with containers before their contents, or the first cycle found
+    fn topological_order(&self) -> Result<Vec<usize>, Vec<usize>> {
+        #[derive(Copy, Clone, PartialEq, Eq)]
+        enum Mark {
+            New,
+            Active,
+            Done,
+        }
+
+        let mut marks = vec![Mark::New; self.len()];
+        let mut order = Vec::with_capacity(self.len());
+
+        for root in 0..self.len() {
+            if marks[root] != Mark::New {
+                continue;
+            }
+
+            // explicit stack of (node, next edge to follow) to not overflow on deep rules
+            let mut stack = vec![(root, 0)];
+            marks[root] = Mark::Active;
+
+            while let Some((node, edge)) = stack.pop() {
+                if let Some(&(next, _)) = self.edges[node].get(edge) {
+                    stack.push((node, edge + 1));
+                    match marks[next] {
+                        Mark::New => {
+                            marks[next] = Mark::Active;
+                            stack.push((next, 0));
+                        }
+                        Mark::Active => {
+                            let start = stack.iter().position(|&(id, _)| id == next).unwrap();
+                            let mut cycle: Vec<usize> = stack[start..].iter().map(|&(id, _)| id).collect();
+                            cycle.push(next);
+                            return Err(cycle);
+                        }
+                        Mark::Done => (),
+                    }
                 } else {
-                    Vec::new()
-                },
-            )
+                    marks[node] = Mark::Done;
+                    order.push(node);
+                }
+            }
+        }
+
+        order.reverse();
+        Ok(order)
+    }
+
+    fn find_cycle(&self) -> Option<Vec<usize>> {
+        self.topological_order().err()
+    }
+
+    // number of bags inside every bag, computed once per colour in reverse topological order
+    fn total_contents(&self) -> Result<Vec<BigUint>, String> {
+        let order = self
+            .topological_order()
+            .map_err(|cycle| format!("Bag rules contain a cycle: {}", self.format_path(&cycle)))?;
+
+        let mut totals = vec![BigUint::zero(); self.len()];
+        for &node in order.iter().rev() {
+            totals[node] = self.edges[node]
+                .iter()
+                .map(|&(next, count)| BigUint::from(count) * (&totals[next] + 1u32))
+                .sum();
+        }
+
+        Ok(totals)
+    }
+
+    // the induced subgraph on the given nodes, keeping their colour names
+    fn subgraph<I>(&self, nodes: I) -> Graph
+    where
+        I: IntoIterator<Item = usize>,
+    {
+        let nodes: BTreeSet<usize> = nodes.into_iter().collect();
+
+        let mut subgraph = Graph::default();
+        for &node in nodes.iter() {
+            let id = subgraph.intern(&self.colours[node]);
+            for &(next, count) in self.edges[node].iter().filter(|(next, _)| nodes.contains(next)) {
+                let next = subgraph.intern(&self.colours[next]);
+                subgraph.edges[id].push((next, count));
+            }
+        }
+
+        subgraph
+    }
+
+    fn reachable(&self, from: usize) -> BTreeSet<usize> {
+        let mut visited = BTreeSet::new();
+        let mut stack = vec![from];
+
+        while let Some(node) = stack.pop() {
+            for &(next, _) in self.edges[node].iter() {
+                if visited.insert(next) {
+                    stack.push(next);
+                }
+            }
+        }
+
+        visited
+    }
+
+    // the colour itself and every colour it (eventually) contains
+    fn closure(&self, colour: &str) -> Result<BTreeSet<usize>, String> {
+        let id = self.id(colour)?;
+        let mut closure = self.reachable(id);
+        closure.insert(id);
+        Ok(closure)
+    }
+
+    // how many of each bag a single `colour` bag holds, summing products of counts along every path
+    fn multiplicities(&self, colour: &str) -> Result<HashMap<String, BigUint>, String> {
+        let below = self.subgraph(self.closure(colour)?);
+        let order = below
+            .topological_order()
+            .map_err(|cycle| format!("Bags below '{}' contain a cycle: {}", colour, below.format_path(&cycle)))?;
+
+        let mut multiplicities = vec![BigUint::zero(); below.len()];
+        multiplicities[below.id(colour)?] = BigUint::from(1u32);
+        for &node in order.iter() {
+            for &(next, count) in below.edges[node].iter() {
+                let carried = &multiplicities[node] * count;
+                multiplicities[next] += carried;
+            }
+        }
+
+        Ok(below.colours.into_iter().zip(multiplicities).collect())
+    }
+
+    fn descendants(&self, colour: &str) -> Result<BTreeSet<&str>, String> {
+        Ok(self
+            .reachable(self.id(colour)?)
+            .into_iter()
+            .map(|id| self.colours[id].as_str())
+            .collect())
+    }
+
+    fn ancestors(&self, colour: &str) -> Result<BTreeSet<String>, String> {
+        Ok(self
+            .reverse()
+            .descendants(colour)?
+            .into_iter()
+            .map(str::to_owned)
+            .collect())
+    }
+}
+
+type Rule<'a> = (&'a str, Vec<(&'a str, u32)>);
+
+fn parse_rule(line: &str) -> Result<Rule<'_>, String> {
+    lazy_static! {
+        static ref RULE: Regex = Regex::new(r"^(\S+ \S+) bags contain (.+)\.$").unwrap();
+        static ref CONTENT: Regex = Regex::new(r"^(\d+) (\S+ \S+) bags?$").unwrap();
+    }
+
+    let fields = RULE
+        .captures(line)
+        .ok_or("expected '<colour> bags contain <contents>.'".to_string())?;
+    let bag = fields.get(1).unwrap().as_str();
+    let contents = fields.get(2).unwrap().as_str();
+
+    if contents == "no other bags" {
+        return Ok((bag, Vec::new()));
+    }
+
+    let contents = contents
+        .split(", ")
+        .map(|token| {
+            let fields = CONTENT
+                .captures(token)
+                .ok_or(format!("expected '<count> <colour> bag(s)', found '{}'", token))?;
+            let count = u32::from_str(fields.get(1).unwrap().as_str())
+                .map_err(|err| format!("invalid count in '{}': {}", token, err))?;
+            if count == 0 {
+                Err(format!("count must be positive in '{}'", token))?
+            }
+            Ok((fields.get(2).unwrap().as_str(), count))
         })
-        .collect::<Graph>())
+        .collect::<Result<Vec<(&str, u32)>, String>>()?;
+
+    Ok((bag, contents))
 }
 
-fn reverse(graph: &Graph) -> Graph {
-    let mut reversed = Graph::with_capacity(graph.len());
+fn parse(filename: &Path) -> Result<Graph, String> {
+    let data = fs::read_to_string(filename).map_err(|err| format!("Failed to read data for day 07: {}", err))?;
+
+    let mut graph = Graph::default();
+    let mut defined = HashSet::new();
+    let mut referenced = HashMap::new();
+
+    for (idx, line) in data.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()) {
+        let (bag, contents) = parse_rule(line).map_err(|err| format!("Invalid rule on line {}: {}", idx + 1, err))?;
 
-    for (node, edges) in graph.iter() {
-        for (next, count) in edges.iter() {
-            reversed
-                .entry(next.to_owned())
-                .and_modify(|edges| edges.push((node.to_owned(), *count)))
-                .or_insert(vec![(node.to_owned(), *count)]);
+        let id = graph.intern(bag);
+        if !defined.insert(id) {
+            Err(format!("Duplicate rule for '{}' bags on line {}", bag, idx + 1))?
+        }
+
+        for (inner, count) in contents {
+            let next = graph.intern(inner);
+            referenced.entry(next).or_insert(idx + 1);
+            graph.edges[id].push((next, count));
         }
     }
 
-    for (node, _) in graph.iter() {
-        reversed.entry(node.to_owned()).or_insert(Vec::new());
+    if let Some((&id, &line)) = referenced
+        .iter()
+        .filter(|(id, _)| !defined.contains(id))
+        .min_by_key(|(_, &line)| line)
+    {
+        Err(format!(
+            "Bag '{}' referenced on line {} has no rule",
+            graph.colours[id], line
+        ))?
     }
 
-    reversed
+    Ok(graph)
 }
 
-#[derive(Clone, Copy, Debug, Default)]
-struct DfsResult {
-    visited: usize,
-    cumulative_weight: i32,
+pub fn bags(filename: &Path, args: &[String]) -> Result<String, String> {
+    let graph = parse(filename)?;
+
+    if args.is_empty() {
+        return match graph.find_cycle() {
+            Some(cycle) => Err(format!("Bag rules contain a cycle: {}", graph.format_path(&cycle))),
+            None => Ok(format!(
+                "Topological order: {}",
+                graph.format_path(&graph.topological_order().unwrap())
+            )),
+        };
+    }
+
+    let colour = args.join(" ");
+    let ancestors = graph.ancestors(&colour)?;
+    let descendants = graph.descendants(&colour)?;
+    let below = graph.subgraph(graph.closure(&colour)?);
+    let total = match below.total_contents() {
+        Ok(totals) => totals[below.id(&colour)?].to_string(),
+        Err(_) => "infinitely many (cycle below this colour)".to_string(),
+    };
+
+    Ok(format!(
+        "{} bags contain a total of {} other bags\ncontained in {} colours: {}\ncontains {} colours: {}",
+        colour,
+        total,
+        ancestors.len(),
+        ancestors.into_iter().collect::<Vec<String>>().join(", "),
+        descendants.len(),
+        descendants.into_iter().collect::<Vec<&str>>().join(", ")
+    ))
 }
 
-fn dfs(bag: &str, graph: &Graph) -> DfsResult {
-    if !graph.contains_key(bag) {
-        return DfsResult::default();
+// `from=COLOUR`, `to=COLOUR` and `highlight=COLOUR`, where words without `=` continue the colour before them as
+// in `from=shiny gold`
+fn parse_options(args: &[String], usage: &str) -> Result<HashMap<String, String>, String> {
+    let mut options: HashMap<String, String> = HashMap::new();
+    let mut current = None;
+
+    for arg in args {
+        match arg.find('=') {
+            Some(idx) => {
+                let key = &arg[..idx];
+                if !["from", "to", "highlight"].contains(&key) {
+                    Err(format!("Unknown option '{}' for export", key))?
+                }
+                options.insert(key.to_string(), arg[idx + 1..].to_string());
+                current = Some(key.to_string());
+            }
+            None => match current.as_ref().and_then(|key| options.get_mut(key)) {
+                Some(colour) => {
+                    colour.push(' ');
+                    colour.push_str(arg);
+                }
+                None => Err(usage.to_string())?,
+            },
+        }
     }
 
-    let mut cumulative_weights = 0;
-    let mut visited = HashSet::with_capacity(graph.len());
-    let mut stack: Vec<(&str, i32)> = graph
-        .get(bag)
-        .unwrap()
+    Ok(options)
+}
+
+fn dot_escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn dot_quote(text: &str) -> String {
+    format!("\"{}\"", dot_escape(text))
+}
+
+fn export_dot(graph: &Graph, highlight: Option<(&str, &HashMap<String, BigUint>)>) -> String {
+    let mut lines = vec![
+        "digraph bags {".to_string(),
+        "    rankdir=LR;".to_string(),
+        "    node [shape=box, fontname=\"Helvetica\"];".to_string(),
+        "    edge [fontname=\"Helvetica\"];".to_string(),
+    ];
+
+    if let Some((colour, multiplicities)) = highlight {
+        let total: BigUint = multiplicities
+            .iter()
+            .filter(|(name, _)| name.as_str() != colour)
+            .map(|(_, multiplicity)| multiplicity)
+            .sum();
+        lines.push(format!(
+            "    label={};",
+            dot_quote(&format!(
+                "one {} bag holds {} bags (sum of node multiplicities)",
+                colour, total
+            ))
+        ));
+    }
+
+    for (id, colour) in graph.colours.iter().enumerate() {
+        let attributes = match highlight {
+            Some((root, _)) if root == colour => {
+                format!("label=\"{}\\nroot\", style=filled, fillcolor=gold", dot_escape(colour))
+            }
+            Some((_, multiplicities)) if multiplicities.contains_key(colour) => format!(
+                "label=\"{}\\nx{}\", color=red",
+                dot_escape(colour),
+                multiplicities[colour]
+            ),
+            _ => format!("label={}", dot_quote(colour)),
+        };
+        lines.push(format!("    {} [{}];", dot_quote(colour), attributes));
+
+        for &(next, count) in graph.edges[id].iter() {
+            let attributes = match highlight {
+                Some((_, multiplicities)) if multiplicities.contains_key(colour) => format!(
+                    "label=\"{} (x{})\", color=red, penwidth=2",
+                    count,
+                    &multiplicities[colour] * count
+                ),
+                _ => format!("label=\"{}\"", count),
+            };
+            lines.push(format!(
+                "    {} -> {} [{}];",
+                dot_quote(colour),
+                dot_quote(&graph.colours[next]),
+                attributes
+            ));
+        }
+    }
+
+    lines.push("}".to_string());
+    lines.join("\n")
+}
+
+fn export_json(graph: &Graph, highlight: Option<(&str, &HashMap<String, BigUint>)>) -> String {
+    let bags: serde_json::Map<String, serde_json::Value> = graph
+        .colours
         .iter()
-        .map(|(next, weight)| (next.as_str(), *weight))
+        .zip(graph.edges.iter())
+        .map(|(colour, edges)| {
+            (
+                colour.clone(),
+                edges
+                    .iter()
+                    .map(|&(next, count)| json!({"colour": graph.colours[next], "count": count}))
+                    .collect(),
+            )
+        })
         .collect();
 
-    while !stack.is_empty() {
-        let frame = stack.pop().unwrap();
+    let mut document = json!({ "bags": bags });
+    if let Some((colour, multiplicities)) = highlight {
+        // big integers are written as strings to not lose precision in other tooling
+        let total: BigUint = multiplicities
+            .iter()
+            .filter(|(name, _)| name.as_str() != colour)
+            .map(|(_, multiplicity)| multiplicity)
+            .sum();
+        document["root"] = json!(colour);
+        document["total"] = json!(total.to_string());
+        document["multiplicities"] = multiplicities
+            .iter()
+            .filter(|(name, _)| graph.index.contains_key(name.as_str()))
+            .map(|(name, multiplicity)| (name.clone(), json!(multiplicity.to_string())))
+            .collect::<serde_json::Map<String, serde_json::Value>>()
+            .into();
+    }
+
+    serde_json::to_string_pretty(&document).unwrap()
+}
 
-        let edges = graph.get(frame.0).unwrap();
-        stack.extend(edges.iter().map(|(next, weight)| (next.as_str(), frame.1 * weight)));
-        visited.insert(frame.0);
-        cumulative_weights = cumulative_weights + frame.1;
+pub fn export(filename: &Path, args: &[String]) -> Result<String, String> {
+    let graph = parse(filename)?;
+
+    let usage = "Usage: export 7 dot|json [from=COLOUR] [to=COLOUR] [highlight=COLOUR]";
+    let (format, options) = match args.split_first() {
+        Some((format, rest)) => (format, parse_options(rest, usage)?),
+        None => Err(usage.to_string())?,
+    };
+
+    let mut nodes: BTreeSet<usize> = (0..graph.len()).collect();
+    if let Some(colour) = options.get("from") {
+        nodes = nodes.intersection(&graph.closure(colour)?).copied().collect();
     }
+    if let Some(colour) = options.get("to") {
+        nodes = nodes.intersection(&graph.reverse().closure(colour)?).copied().collect();
+    }
+    let subgraph = graph.subgraph(nodes);
+
+    let highlight = match options.get("highlight") {
+        Some(colour) => Some((colour.as_str(), graph.multiplicities(colour)?)),
+        None => None,
+    };
+    let highlight = highlight
+        .as_ref()
+        .map(|(colour, multiplicities)| (*colour, multiplicities));
 
-    DfsResult {
-        visited: visited.len(),
-        cumulative_weight: cumulative_weights,
+    match format.as_str() {
+        "dot" => Ok(export_dot(&subgraph, highlight)),
+        "json" => Ok(export_json(&subgraph, highlight)),
+        other => Err(format!("Unknown export format '{}', expected dot or json", other)),
     }
 }
 
 pub fn part01(filename: &Path) -> Result<String, String> {
-    let reversed = reverse(&parse(filename)?);
+    let graph = parse(filename)?;
 
     let bag = "shiny gold";
     Ok(format!(
         "Bags that eventually contains {} bags: {}",
         bag,
-        dfs(bag, &reversed).visited
+        graph.ancestors(bag)?.len()
     ))
 }
 
@@ -105,9 +497,11 @@ pub fn part02(filename: &Path) -> Result<String, String> {
     let graph = parse(filename)?;
 
     let bag = "shiny gold";
+    // only the bags inside shiny gold count, cycles elsewhere do not matter
+    let below = graph.subgraph(graph.closure(bag)?);
     Ok(format!(
         "{} bags contains a total of other bags: {}",
         bag,
-        dfs(bag, &graph).cumulative_weight
+        below.total_contents()?[below.id(bag)?]
     ))
 }
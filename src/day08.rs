@@ -1,96 +1,1348 @@
-use std::collections::HashSet;
+use regex::Regex;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::fmt;
 use std::fs;
-use std::iter::FromIterator;
+use std::io::{self, BufRead, Write};
 use std::path::Path;
 use std::str::FromStr;
 
-#[derive(Copy, Clone, Debug)]
+use lazy_static::lazy_static;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Register {
+    Acc,
+    A,
+    B,
+    C,
+    D,
+}
+
+const REGISTERS: [Register; 5] = [Register::Acc, Register::A, Register::B, Register::C, Register::D];
+
+impl FromStr for Register {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "acc" => Ok(Register::Acc),
+            "a" => Ok(Register::A),
+            "b" => Ok(Register::B),
+            "c" => Ok(Register::C),
+            "d" => Ok(Register::D),
+            other => Err(format!("Unknown register: {}", other)),
+        }
+    }
+}
+
+impl fmt::Display for Register {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Register::Acc => write!(f, "acc"),
+            Register::A => write!(f, "a"),
+            Register::B => write!(f, "b"),
+            Register::C => write!(f, "c"),
+            Register::D => write!(f, "d"),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Operand {
+    Immediate(i32),
+    Register(Register),
+}
+
+impl fmt::Display for Operand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Operand::Immediate(v) => write!(f, "{}", v),
+            Operand::Register(r) => write!(f, "{}", r),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Condition {
+    Zero,
+    NonZero,
+    Positive,
+    Negative,
+}
+
+impl Condition {
+    fn holds(&self, value: i32) -> bool {
+        match self {
+            Condition::Zero => value == 0,
+            Condition::NonZero => value != 0,
+            Condition::Positive => value > 0,
+            Condition::Negative => value < 0,
+        }
+    }
+
+    fn mnemonic(&self) -> &'static str {
+        match self {
+            Condition::Zero => "jz",
+            Condition::NonZero => "jnz",
+            Condition::Positive => "jgz",
+            Condition::Negative => "jlz",
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 enum Instruction {
     Acc(i32),
     Jmp(i32),
     Nop(i32),
+    Add(Register, Operand),
+    Mov(Register, Operand),
+    Jcc(Condition, Register, i32),
+    Hlt,
+}
+
+impl Instruction {
+    fn target(&self) -> Option<i32> {
+        match self {
+            Instruction::Jmp(offset) | Instruction::Nop(offset) | Instruction::Jcc(_, _, offset) => Some(*offset),
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for Instruction {
+    type Err = String;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let program = assemble(line)?;
+        match program.as_slice() {
+            [instruction] => Ok(*instruction),
+            _ => Err(format!("Expected exactly one instruction, found {}", program.len())),
+        }
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Instruction::Acc(v) => write!(f, "acc {:+}", v),
+            Instruction::Jmp(v) => write!(f, "jmp {:+}", v),
+            Instruction::Nop(v) => write!(f, "nop {:+}", v),
+            Instruction::Add(r, o) => write!(f, "add {}, {}", r, o),
+            Instruction::Mov(r, o) => write!(f, "mov {}, {}", r, o),
+            Instruction::Jcc(c, r, v) => write!(f, "{} {}, {:+}", c.mnemonic(), r, v),
+            Instruction::Hlt => write!(f, "hlt"),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct AsmError {
+    line: usize,
+    column: usize,
+    width: usize,
+    message: String,
+}
+
+impl AsmError {
+    fn new(line: usize, token: &(usize, &str), message: String) -> Self {
+        AsmError {
+            line,
+            column: token.0,
+            width: token.1.len(),
+            message,
+        }
+    }
+
+    fn render(&self, source: &str) -> String {
+        let text = source.lines().nth(self.line).unwrap_or("");
+        let column = text[..self.column].chars().count();
+        let width = usize::max(1, text[self.column..self.column + self.width].chars().count());
+        let gutter = (self.line + 1).to_string();
+
+        format!(
+            "error: {}\n{} --> {}:{}\n{} |\n{} | {}\n{} | {}{}",
+            self.message,
+            " ".repeat(gutter.len()),
+            self.line + 1,
+            column + 1,
+            " ".repeat(gutter.len()),
+            gutter,
+            text,
+            " ".repeat(gutter.len()),
+            " ".repeat(column),
+            "^".repeat(width)
+        )
+    }
+}
+
+// splits a line into (byte offset, token) pairs, dropping comments and treating commas as whitespace
+fn tokenize(line: &str) -> Vec<(usize, &str)> {
+    let code = match line.find(&[';', '#'][..]) {
+        Some(idx) => &line[..idx],
+        None => line,
+    };
+
+    let mut tokens = Vec::new();
+    let mut start = None;
+    for (i, c) in code.char_indices() {
+        if c.is_whitespace() || c == ',' {
+            if let Some(s) = start.take() {
+                tokens.push((s, &code[s..i]));
+            }
+        } else if start.is_none() {
+            start = Some(i);
+        }
+    }
+    if let Some(s) = start {
+        tokens.push((s, &code[s..]));
+    }
+
+    tokens
+}
+
+fn assemble_instruction(
+    line: usize,
+    pc: usize,
+    tokens: &[(usize, &str)],
+    labels: &HashMap<&str, usize>,
+) -> Result<Instruction, AsmError> {
+    let mnemonic = &tokens[0];
+    let operands = &tokens[1..];
+
+    let arity = match mnemonic.1 {
+        "hlt" => 0,
+        "acc" | "jmp" | "nop" => 1,
+        "add" | "mov" | "jz" | "jnz" | "jgz" | "jlz" => 2,
+        other => return Err(AsmError::new(line, mnemonic, format!("unknown mnemonic '{}'", other))),
+    };
+    if operands.len() != arity {
+        let token = operands.get(arity).unwrap_or(mnemonic);
+        return Err(AsmError::new(
+            line,
+            token,
+            format!("'{}' takes {} operand(s), found {}", mnemonic.1, arity, operands.len()),
+        ));
+    }
+
+    let immediate = |token: &(usize, &str)| {
+        i32::from_str(token.1).map_err(|err| AsmError::new(line, token, format!("invalid number: {}", err)))
+    };
+    let register = |token: &(usize, &str)| {
+        Register::from_str(token.1).map_err(|err| AsmError::new(line, token, err.to_lowercase()))
+    };
+    let operand = |token: &(usize, &str)| match Register::from_str(token.1) {
+        Ok(r) => Ok(Operand::Register(r)),
+        Err(_) => immediate(token).map(Operand::Immediate),
+    };
+    let target = |token: &(usize, &str)| {
+        if let Ok(offset) = i32::from_str(token.1) {
+            Ok(offset)
+        } else if let Some(&target) = labels.get(token.1) {
+            Ok(target as i32 - pc as i32)
+        } else {
+            Err(AsmError::new(line, token, format!("undefined label '{}'", token.1)))
+        }
+    };
+
+    Ok(match mnemonic.1 {
+        "hlt" => Instruction::Hlt,
+        "acc" => Instruction::Acc(immediate(&operands[0])?),
+        "jmp" => Instruction::Jmp(target(&operands[0])?),
+        "nop" => Instruction::Nop(target(&operands[0])?),
+        "add" => Instruction::Add(register(&operands[0])?, operand(&operands[1])?),
+        "mov" => Instruction::Mov(register(&operands[0])?, operand(&operands[1])?),
+        "jz" => Instruction::Jcc(Condition::Zero, register(&operands[0])?, target(&operands[1])?),
+        "jnz" => Instruction::Jcc(Condition::NonZero, register(&operands[0])?, target(&operands[1])?),
+        "jgz" => Instruction::Jcc(Condition::Positive, register(&operands[0])?, target(&operands[1])?),
+        "jlz" => Instruction::Jcc(Condition::Negative, register(&operands[0])?, target(&operands[1])?),
+        _ => unreachable!(),
+    })
+}
+
+fn assemble(source: &str) -> Result<Vec<Instruction>, String> {
+    lazy_static! {
+        static ref LABEL: Regex = Regex::new(r"^[A-Za-z_][A-Za-z0-9_]*:$").unwrap();
+    }
+
+    let mut errors = Vec::new();
+    let mut labels: HashMap<&str, usize> = HashMap::new();
+    let mut statements = Vec::new();
+
+    // first pass: resolve label addresses
+    for (line, text) in source.lines().enumerate() {
+        let mut tokens = tokenize(text);
+        while let Some(token) = tokens.first().copied() {
+            if !token.1.ends_with(':') {
+                break;
+            }
+            tokens.remove(0);
+
+            let name = &token.1[..token.1.len() - 1];
+            if !LABEL.is_match(token.1) {
+                errors.push(AsmError::new(line, &token, format!("invalid label name '{}'", name)));
+            } else if labels.insert(name, statements.len()).is_some() {
+                errors.push(AsmError::new(line, &token, format!("duplicate label '{}'", name)));
+            }
+        }
+
+        if !tokens.is_empty() {
+            statements.push((line, tokens));
+        }
+    }
+
+    // second pass: assemble instructions
+    let mut program = Vec::with_capacity(statements.len());
+    for (pc, (line, tokens)) in statements.iter().enumerate() {
+        match assemble_instruction(*line, pc, tokens, &labels) {
+            Ok(instruction) => program.push(instruction),
+            Err(err) => errors.push(err),
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(program)
+    } else {
+        errors.sort_by_key(|err| (err.line, err.column));
+        Err(errors
+            .iter()
+            .map(|err| err.render(source))
+            .collect::<Vec<String>>()
+            .join("\n\n"))
+    }
+}
+
+fn disassemble(program: &[Instruction], with_labels: bool) -> String {
+    let targets: BTreeSet<usize> = if with_labels {
+        program
+            .iter()
+            .enumerate()
+            .filter_map(|(pc, instruction)| instruction.target().map(|offset| pc as i32 + offset))
+            .filter(|&target| 0 <= target && target as usize <= program.len())
+            .map(|target| target as usize)
+            .collect()
+    } else {
+        BTreeSet::new()
+    };
+
+    let mut lines = Vec::with_capacity(program.len() + targets.len());
+    for (pc, instruction) in program.iter().enumerate() {
+        if targets.contains(&pc) {
+            lines.push(format!("L{}:", pc));
+        }
+
+        let target = instruction
+            .target()
+            .map(|offset| pc as i32 + offset)
+            .filter(|&target| 0 <= target && targets.contains(&(target as usize)));
+        lines.push(match (target, instruction) {
+            (Some(target), Instruction::Jmp(_)) => format!("    jmp L{}", target),
+            (Some(target), Instruction::Nop(_)) => format!("    nop L{}", target),
+            (Some(target), Instruction::Jcc(c, r, _)) => format!("    {} {}, L{}", c.mnemonic(), r, target),
+            _ if with_labels => format!("    {}", instruction),
+            _ => instruction.to_string(),
+        });
+    }
+    if targets.contains(&program.len()) {
+        lines.push(format!("L{}:", program.len()));
+    }
+
+    lines.join("\n")
 }
 
 fn parse(filename: &Path) -> Result<Vec<Instruction>, String> {
-    fs::read_to_string(filename)
-        .map_err(|err| format!("Failed to read data for day 08: {}", err))?
-        .lines()
-        .map(|line| match &line[..3] {
-            "acc" => {
-                let value =
-                    i32::from_str(&line[4..]).map_err(|err| format!("Failed to parse ACC increment: {}", err))?;
-                Ok(Instruction::Acc(value))
-            }
-            "jmp" => {
-                let value =
-                    i32::from_str(&line[4..]).map_err(|err| format!("Failed to parse JMP increment: {}", err))?;
-                Ok(Instruction::Jmp(value))
-            }
-            "nop" => {
-                let value =
-                    i32::from_str(&line[4..]).map_err(|err| format!("Failed to parse NOP increment: {}", err))?;
-                Ok(Instruction::Nop(value))
-            }
-            e => Err(format!("Unknown instruction: {}", e)),
-        })
-        .collect::<Result<Vec<Instruction>, String>>()
+    let source = fs::read_to_string(filename).map_err(|err| format!("Failed to read data for day 08: {}", err))?;
+    assemble(&source).map_err(|err| format!("Failed to assemble {}:\n{}", filename.display(), err))
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Status {
+    Ready,
+    Looped,
+    Halted,
+    Terminated,
+    OutOfBounds,
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Status::Ready => write!(f, "ready"),
+            Status::Looped => write!(f, "looped"),
+            Status::Halted => write!(f, "halted"),
+            Status::Terminated => write!(f, "terminated"),
+            Status::OutOfBounds => write!(f, "out of bounds"),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+struct Step {
+    pub pc: i32,
+    pub instruction: Instruction,
+    pub registers: [i32; 5],
+}
+
+fn format_registers(registers: &[i32; 5]) -> String {
+    REGISTERS
+        .iter()
+        .map(|r| format!("{}={}", r, registers[*r as usize]))
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Comparison {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Comparison {
+    fn holds(&self, lhs: i32, rhs: i32) -> bool {
+        match self {
+            Comparison::Eq => lhs == rhs,
+            Comparison::Ne => lhs != rhs,
+            Comparison::Lt => lhs < rhs,
+            Comparison::Le => lhs <= rhs,
+            Comparison::Gt => lhs > rhs,
+            Comparison::Ge => lhs >= rhs,
+        }
+    }
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Comparison::Eq => write!(f, "=="),
+            Comparison::Ne => write!(f, "!="),
+            Comparison::Lt => write!(f, "<"),
+            Comparison::Le => write!(f, "<="),
+            Comparison::Gt => write!(f, ">"),
+            Comparison::Ge => write!(f, ">="),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Breakpoint {
+    Pc(i32),
+    Register(Register, Comparison, i32),
+}
+
+impl FromStr for Breakpoint {
+    type Err = String;
+
+    fn from_str(spec: &str) -> Result<Self, Self::Err> {
+        lazy_static! {
+            static ref RE: Regex = Regex::new(r"^([a-z]+)\s*(==|=|!=|<=|>=|<|>)\s*([+-]?\d+)$").unwrap();
+        }
+
+        let spec = spec.trim();
+        if let Ok(pc) = i32::from_str(spec) {
+            return Ok(Breakpoint::Pc(pc));
+        }
+
+        let groups = RE
+            .captures(spec)
+            .ok_or(format!("Invalid breakpoint, expected PC or REG<op>VALUE: {}", spec))?;
+        let value = i32::from_str(groups.get(3).unwrap().as_str())
+            .map_err(|err| format!("Failed to parse breakpoint value: {}", err))?;
+        let comparison = match groups.get(2).unwrap().as_str() {
+            "=" | "==" => Comparison::Eq,
+            "!=" => Comparison::Ne,
+            "<" => Comparison::Lt,
+            "<=" => Comparison::Le,
+            ">" => Comparison::Gt,
+            ">=" => Comparison::Ge,
+            _ => unreachable!(),
+        };
+
+        match (groups.get(1).unwrap().as_str(), comparison) {
+            ("pc", Comparison::Eq) => Ok(Breakpoint::Pc(value)),
+            ("pc", _) => Err("PC breakpoints only support equality".to_string()),
+            (register, comparison) => Ok(Breakpoint::Register(Register::from_str(register)?, comparison, value)),
+        }
+    }
 }
 
-fn interpreter(program: &Vec<Instruction>) -> Result<i32, i32> {
-    let mut accumulator = 0;
+impl fmt::Display for Breakpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Breakpoint::Pc(pc) => write!(f, "pc == {}", pc),
+            Breakpoint::Register(register, comparison, value) => write!(f, "{} {} {}", register, comparison, value),
+        }
+    }
+}
 
-    let mut executed = HashSet::new();
-    let mut pc: i32 = 0;
+#[derive(Clone, Debug)]
+struct Vm {
+    program: Vec<Instruction>,
+    pc: i32,
+    registers: [i32; 5],
+    halted: bool,
+    executed: Vec<bool>,
+    control: [bool; 5],
+    seen: HashSet<(i32, [i32; 5])>,
+    trace: Vec<Step>,
+    breakpoints: Vec<Breakpoint>,
+}
+
+// registers that may influence a conditional jump, directly or by being copied into a tested register
+fn control_registers(program: &[Instruction]) -> [bool; 5] {
+    let mut control = [false; 5];
+    for instruction in program {
+        if let Instruction::Jcc(_, r, _) = instruction {
+            control[*r as usize] = true;
+        }
+    }
 
     loop {
-        if pc as usize == program.len() {
-            break Ok(accumulator);
-        } else if executed.contains(&pc) {
-            break Err(accumulator);
+        let mut changed = false;
+        for instruction in program {
+            if let Instruction::Add(r, Operand::Register(s)) | Instruction::Mov(r, Operand::Register(s)) = instruction {
+                if control[*r as usize] && !control[*s as usize] {
+                    control[*s as usize] = true;
+                    changed = true;
+                }
+            }
+        }
+        if !changed {
+            break control;
+        }
+    }
+}
+
+impl Vm {
+    fn new(program: Vec<Instruction>) -> Self {
+        let executed = vec![false; program.len()];
+        let control = control_registers(&program);
+        Vm {
+            program,
+            pc: 0,
+            registers: [0; 5],
+            halted: false,
+            executed,
+            control,
+            seen: HashSet::new(),
+            trace: Vec::new(),
+            breakpoints: Vec::new(),
+        }
+    }
+
+    fn reset(&mut self) {
+        self.pc = 0;
+        self.registers = [0; 5];
+        self.halted = false;
+        self.executed.iter_mut().for_each(|executed| *executed = false);
+        self.seen.clear();
+        self.trace.clear();
+    }
+
+    // the part of the machine state that decides control flow, revisiting it means the program loops forever
+    fn control_state(&self) -> (i32, [i32; 5]) {
+        let mut registers = [0; 5];
+        for (i, register) in registers.iter_mut().enumerate() {
+            if self.control[i] {
+                *register = self.registers[i];
+            }
         }
+        (self.pc, registers)
+    }
+
+    fn accumulator(&self) -> i32 {
+        self.registers[Register::Acc as usize]
+    }
+
+    fn status(&self) -> Status {
+        if self.halted {
+            Status::Halted
+        } else if self.pc as usize == self.program.len() {
+            Status::Terminated
+        } else if self.pc < 0 || self.pc as usize > self.program.len() {
+            Status::OutOfBounds
+        } else if self.seen.contains(&self.control_state()) {
+            Status::Looped
+        } else {
+            Status::Ready
+        }
+    }
 
-        executed.insert(pc);
-        let instruction = *&program[pc as usize];
-        pc = pc + 1;
+    fn current(&self) -> Option<Instruction> {
+        if self.pc < 0 || self.halted {
+            None
+        } else {
+            self.program.get(self.pc as usize).copied()
+        }
+    }
+
+    fn value(&self, operand: &Operand) -> i32 {
+        match operand {
+            Operand::Immediate(v) => *v,
+            Operand::Register(r) => self.registers[*r as usize],
+        }
+    }
 
+    // executes the instruction at pc, even if it has been executed before
+    fn step(&mut self) -> Status {
+        let instruction = match self.current() {
+            Some(instruction) => instruction,
+            None => return self.status(),
+        };
+
+        self.executed[self.pc as usize] = true;
+        self.seen.insert(self.control_state());
+        let pc = self.pc;
+        let mut next = pc.wrapping_add(1);
         match instruction {
-            Instruction::Acc(v) => accumulator = accumulator + v,
-            Instruction::Jmp(v) => pc = pc + v - 1,
-            Instruction::Nop(_) => continue,
+            Instruction::Acc(v) => {
+                let acc = &mut self.registers[Register::Acc as usize];
+                *acc = acc.wrapping_add(v);
+            }
+            Instruction::Jmp(v) => next = pc.wrapping_add(v),
+            Instruction::Nop(_) => (),
+            Instruction::Add(r, o) => {
+                let value = self.value(&o);
+                self.registers[r as usize] = self.registers[r as usize].wrapping_add(value);
+            }
+            Instruction::Mov(r, o) => self.registers[r as usize] = self.value(&o),
+            Instruction::Jcc(c, r, v) => {
+                if c.holds(self.registers[r as usize]) {
+                    next = pc.wrapping_add(v);
+                }
+            }
+            Instruction::Hlt => {
+                self.halted = true;
+                next = pc;
+            }
+        }
+        self.pc = next;
+
+        self.trace.push(Step {
+            pc,
+            instruction,
+            registers: self.registers,
+        });
+        self.status()
+    }
+
+    // always executes at least one instruction, then stops on loop, termination or when `stop` holds
+    fn run_until<F>(&mut self, stop: F) -> Status
+    where
+        F: Fn(&Self) -> bool,
+    {
+        loop {
+            let status = self.step();
+            if status != Status::Ready || stop(self) {
+                break status;
+            }
         }
     }
+
+    fn run(&mut self) -> Status {
+        self.run_until(|vm| vm.hit_breakpoint().is_some())
+    }
+
+    fn hit_breakpoint(&self) -> Option<&Breakpoint> {
+        self.breakpoints.iter().find(|breakpoint| match breakpoint {
+            Breakpoint::Pc(pc) => self.pc == *pc,
+            Breakpoint::Register(register, comparison, value) => {
+                comparison.holds(self.registers[*register as usize], *value)
+            }
+        })
+    }
+
+    fn patch(&mut self, pc: usize, instruction: Instruction) -> Result<Instruction, String> {
+        let slot = self.program.get_mut(pc).ok_or(format!("No instruction at pc {}", pc))?;
+        let old = std::mem::replace(slot, instruction);
+        self.control = control_registers(&self.program);
+        self.seen.clear();
+        Ok(old)
+    }
 }
 
-fn replace_instruction(program: &Vec<Instruction>, idx: usize, instruction: &Instruction) -> Vec<Instruction> {
-    Vec::from_iter(
-        program[..idx]
-            .iter()
-            .chain(&[*instruction])
-            .chain(&program[(idx + 1)..])
-            .copied(),
-    )
+fn interpreter(program: &[Instruction]) -> Result<i32, i32> {
+    let mut vm = Vm::new(program.to_vec());
+
+    match vm.run_until(|_| false) {
+        Status::Terminated | Status::Halted => Ok(vm.accumulator()),
+        _ => Err(vm.accumulator()),
+    }
 }
 
-fn find_correction(program: &Vec<Instruction>) -> Result<i32, String> {
-    for (i, instruction) in program.iter().enumerate() {
-        match instruction {
-            Instruction::Jmp(v) => {
-                if let Ok(accumulator) = interpreter(&replace_instruction(&program, i, &Instruction::Nop(*v))) {
-                    return Ok(accumulator);
+fn successors(pc: usize, instruction: &Instruction, len: usize) -> Vec<usize> {
+    let jump = |offset: i32| {
+        let target = pc as i64 + offset as i64;
+        if 0 <= target && target <= len as i64 {
+            Some(target as usize)
+        } else {
+            None
+        }
+    };
+
+    match instruction {
+        Instruction::Jmp(offset) => jump(*offset).into_iter().collect(),
+        Instruction::Jcc(_, _, offset) => std::iter::once(pc + 1).chain(jump(*offset)).collect(),
+        Instruction::Hlt => vec![len],
+        _ => vec![pc + 1],
+    }
+}
+
+// nodes are the instruction pcs plus an exit node at `program.len()`, conditional jumps have both edges
+#[derive(Clone, Debug)]
+struct ControlFlowGraph {
+    predecessors: Vec<Vec<usize>>,
+}
+
+impl ControlFlowGraph {
+    fn new(program: &[Instruction]) -> Self {
+        let mut predecessors = vec![Vec::new(); program.len() + 1];
+        for (pc, instruction) in program.iter().enumerate() {
+            for next in successors(pc, instruction, program.len()) {
+                predecessors[next].push(pc);
+            }
+        }
+
+        ControlFlowGraph { predecessors }
+    }
+
+    fn exit(&self) -> usize {
+        self.predecessors.len() - 1
+    }
+
+    // pcs from which the exit node can be reached
+    fn terminating(&self) -> Vec<bool> {
+        let mut terminating = vec![false; self.predecessors.len()];
+        let mut stack = vec![self.exit()];
+        terminating[self.exit()] = true;
+
+        while let Some(pc) = stack.pop() {
+            for &prev in self.predecessors[pc].iter() {
+                if !terminating[prev] {
+                    terminating[prev] = true;
+                    stack.push(prev);
                 }
             }
-            Instruction::Nop(v) => {
-                if let Ok(accumulator) = interpreter(&replace_instruction(&program, i, &Instruction::Jmp(*v))) {
-                    return Ok(accumulator);
+        }
+
+        terminating
+    }
+
+    // for programs of only `acc`, `jmp`, `nop` and `hlt` every pc has at most one successor, so the accumulator
+    // gained on the way from each terminating pc to the exit is fixed
+    fn exit_accumulators(&self, program: &[Instruction]) -> Option<Vec<Option<i32>>> {
+        if !program.iter().all(|instruction| {
+            matches!(
+                instruction,
+                Instruction::Acc(_) | Instruction::Jmp(_) | Instruction::Nop(_) | Instruction::Hlt
+            )
+        }) {
+            return None;
+        }
+
+        let mut accumulators = vec![None; self.predecessors.len()];
+        let mut stack = vec![self.exit()];
+        accumulators[self.exit()] = Some(0);
+
+        while let Some(pc) = stack.pop() {
+            let gained = accumulators[pc].unwrap();
+            for &prev in self.predecessors[pc].iter() {
+                if accumulators[prev].is_none() {
+                    let own = match program[prev] {
+                        Instruction::Acc(v) => v,
+                        _ => 0,
+                    };
+                    accumulators[prev] = Some(own.wrapping_add(gained));
+                    stack.push(prev);
+                }
+            }
+        }
+
+        Some(accumulators)
+    }
+}
+
+fn flipped(instruction: &Instruction) -> Option<Instruction> {
+    match instruction {
+        Instruction::Jmp(v) => Some(Instruction::Nop(*v)),
+        Instruction::Nop(v) => Some(Instruction::Jmp(*v)),
+        _ => None,
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+struct Repair {
+    pub pc: usize,
+    pub original: Instruction,
+    pub replacement: Instruction,
+    pub accumulator: i32,
+}
+
+fn find_correction(program: &[Instruction]) -> Result<Repair, String> {
+    let terminating = ControlFlowGraph::new(program).terminating();
+
+    let mut vm = Vm::new(program.to_vec());
+    if let Status::Terminated | Status::Halted = vm.run_until(|_| false) {
+        return Err("Program already terminates, nothing to correct".to_string());
+    }
+
+    // only a flip on the executed path can change the outcome, and it must lead into the terminating set
+    let mut checked = vec![false; program.len()];
+    for step in vm.trace.iter() {
+        let pc = step.pc as usize;
+        if checked[pc] {
+            continue;
+        }
+        checked[pc] = true;
+
+        if let Some(replacement) = flipped(&step.instruction) {
+            if successors(pc, &replacement, program.len())
+                .iter()
+                .any(|&next| terminating[next])
+            {
+                let mut candidate = program.to_vec();
+                candidate[pc] = replacement;
+                if let Ok(accumulator) = interpreter(&candidate) {
+                    return Ok(Repair {
+                        pc,
+                        original: step.instruction,
+                        replacement,
+                        accumulator,
+                    });
                 }
             }
-            _ => (),
         }
     }
 
     Err("Could not find correction...".to_string())
 }
 
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+enum Edit {
+    Flip,
+    Delete,
+    Retarget(i32),
+}
+
+impl Edit {
+    fn name(&self) -> &'static str {
+        match self {
+            Edit::Flip => "flip",
+            Edit::Delete => "delete",
+            Edit::Retarget(_) => "retarget",
+        }
+    }
+}
+
+// edits keyed by the pc in the unedited program, at most one edit per instruction
+type EditSet = BTreeMap<usize, Edit>;
+
+struct EditRepair {
+    edits: EditSet,
+    accumulator: i32,
+    // number of retarget destinations leading to the same accumulator, the nearest one is in `edits`
+    alike: usize,
+}
+
+const MAX_REPAIR_STATES: usize = 1_000_000;
+const REPAIR_EXAMPLES: usize = 3;
+
+fn retargeted(instruction: &Instruction, offset: i32) -> Option<Instruction> {
+    match instruction {
+        Instruction::Jmp(_) => Some(Instruction::Jmp(offset)),
+        Instruction::Jcc(c, r, _) => Some(Instruction::Jcc(*c, *r, offset)),
+        _ => None,
+    }
+}
+
+// returns the edited program together with the original pc of every remaining instruction
+fn apply_edits(program: &[Instruction], edits: &EditSet) -> (Vec<Instruction>, Vec<usize>) {
+    let mut edited = Vec::with_capacity(program.len());
+    let mut origin = Vec::with_capacity(program.len());
+
+    for (pc, instruction) in program.iter().enumerate() {
+        let instruction = match edits.get(&pc) {
+            None => *instruction,
+            Some(Edit::Delete) => continue,
+            Some(Edit::Flip) => flipped(instruction).unwrap(),
+            Some(Edit::Retarget(offset)) => retargeted(instruction, *offset).unwrap(),
+        };
+        edited.push(instruction);
+        origin.push(pc);
+    }
+
+    (edited, origin)
+}
+
+fn describe_edits(program: &[Instruction], edits: &EditSet) -> String {
+    edits
+        .iter()
+        .map(|(&pc, edit)| match edit {
+            Edit::Flip => format!("flip pc {}: {} -> {}", pc, program[pc], flipped(&program[pc]).unwrap()),
+            Edit::Delete => format!("delete pc {}: {}", pc, program[pc]),
+            Edit::Retarget(offset) => format!(
+                "retarget pc {}: {} -> {}",
+                pc,
+                program[pc],
+                retargeted(&program[pc], *offset).unwrap()
+            ),
+        })
+        .collect::<Vec<String>>()
+        .join("; ")
+}
+
+// whether `child` contains an earlier repair, those all include the edit at `pc` as the rest of `child` was
+// already checked when it was queued
+fn extends_repair(repairs: &HashSet<EditSet>, child: &EditSet, pc: usize) -> bool {
+    let others: Vec<(&usize, &Edit)> = child.iter().filter(|(&other, _)| other != pc).collect();
+    (0..1usize << others.len()).any(|mask| {
+        let mut subset = EditSet::new();
+        subset.insert(pc, child[&pc]);
+        subset.extend(
+            others
+                .iter()
+                .enumerate()
+                .filter(|(bit, _)| mask & (1 << bit) != 0)
+                .map(|(_, (&pc, &edit))| (pc, edit)),
+        );
+        repairs.contains(&subset)
+    })
+}
+
+// Breadth-first search over edit sets, so repairs are found in order of edit count. Only instructions executed by
+// the failing run are edited, and supersets of an earlier repair are dropped. A flipped or retargeted instruction
+// can only make the program terminate when one of its new successors reaches termination, which is checked in the
+// control flow graph before running it. Jumps are only retargeted into that set, destinations leaving the same
+// accumulator behind count as one retarget, and a retarget that still does not terminate is not extended further.
+// Returns the repairs and, when the search was cut short, the smallest edit count that may be missing repairs.
+fn find_repairs(program: &[Instruction], max_edits: usize) -> Result<(Vec<EditRepair>, Option<usize>), String> {
+    if interpreter(program).is_ok() {
+        return Err("Program already terminates, nothing to repair".to_string());
+    }
+
+    let mut repairs: Vec<EditRepair> = Vec::new();
+    // repairs without a retarget, and the retarget offsets repairing the other edits for each retargeted pc
+    let mut found: HashSet<EditSet> = HashSet::new();
+    let mut found_retargets: HashMap<(EditSet, usize), HashSet<i32>> = HashMap::new();
+    let mut visited: HashSet<EditSet> = HashSet::new();
+    let mut queue: VecDeque<EditSet> = VecDeque::new();
+    let mut states = 0;
+    let mut truncated = None;
+    queue.push_back(EditSet::new());
+
+    'search: while let Some(edits) = queue.pop_front() {
+        let (edited, origin) = apply_edits(program, &edits);
+        let graph = ControlFlowGraph::new(&edited);
+        let terminating = graph.terminating();
+        let accumulators = graph.exit_accumulators(&edited);
+
+        let mut vm = Vm::new(edited.clone());
+        vm.run_until(|_| false);
+
+        // the accumulator when each pc is first reached
+        let mut reached = vec![None; edited.len()];
+        let mut accumulator = 0;
+        for step in vm.trace.iter() {
+            reached[step.pc as usize].get_or_insert(accumulator);
+            accumulator = step.registers[Register::Acc as usize];
+        }
+
+        let mut executed: Vec<usize> = vm.trace.iter().map(|step| step.pc as usize).collect();
+        executed.sort_unstable();
+        executed.dedup();
+
+        // none of the proper subsets of `edits` is a repair, but one with a retarget added may be
+        let subsets: Vec<EditSet> = (0..(1usize << edits.len()) - 1)
+            .map(|mask| {
+                edits
+                    .iter()
+                    .enumerate()
+                    .filter(|(bit, _)| mask & (1 << bit) != 0)
+                    .map(|(_, (&pc, &edit))| (pc, edit))
+                    .collect()
+            })
+            .collect();
+
+        for pc in executed {
+            let original_pc = origin[pc];
+            if edits.contains_key(&original_pc) {
+                continue;
+            }
+
+            let mut candidates = vec![Edit::Delete];
+            if flipped(&edited[pc]).is_some() {
+                candidates.push(Edit::Flip);
+            }
+
+            for edit in candidates {
+                let mut child = edits.clone();
+                child.insert(original_pc, edit);
+
+                if visited.contains(&child) || extends_repair(&found, &child, original_pc) {
+                    continue;
+                }
+                if states >= MAX_REPAIR_STATES {
+                    truncated = Some(child.len());
+                    break 'search;
+                }
+                states += 1;
+                visited.insert(child.clone());
+
+                let replacement = match edit {
+                    Edit::Flip => flipped(&edited[pc]),
+                    _ => None,
+                };
+                let may_terminate = match replacement {
+                    Some(replacement) => successors(pc, &replacement, edited.len())
+                        .iter()
+                        .any(|&next| terminating[next]),
+                    None => true,
+                };
+
+                // without registers the graph decides termination, and the run up to `pc` is the failing one
+                let outcome = match (&accumulators, replacement) {
+                    (Some(accumulators), Some(replacement)) => {
+                        let next = successors(pc, &replacement, edited.len()).first().copied();
+                        Some(match next.and_then(|next| accumulators[next]) {
+                            Some(gained) => Ok(reached[pc].unwrap().wrapping_add(gained)),
+                            None => Err(0),
+                        })
+                    }
+                    _ => may_terminate.then(|| interpreter(&apply_edits(program, &child).0)),
+                };
+
+                match outcome {
+                    Some(Ok(accumulator)) => {
+                        found.insert(child.clone());
+                        repairs.push(EditRepair {
+                            edits: child,
+                            accumulator,
+                            alike: 1,
+                        });
+                    }
+                    _ if child.len() < max_edits => queue.push_back(child),
+                    _ => (),
+                }
+            }
+
+            let current = match retargeted(&edited[pc], 0).and_then(|_| edited[pc].target()) {
+                Some(current) => current,
+                None => continue,
+            };
+            let superfluous: HashSet<i32> = subsets
+                .iter()
+                .filter_map(|subset| found_retargets.get(&(subset.clone(), original_pc)))
+                .flatten()
+                .copied()
+                .collect();
+
+            // the retargets leaving the same accumulator behind where the graph knows it, nearest offset first
+            let mut alike: BTreeMap<(Option<i32>, usize), Vec<i32>> = BTreeMap::new();
+            for target in (0..=edited.len()).filter(|&target| terminating[target]) {
+                let offset = target as i32 - pc as i32;
+                if offset == current || superfluous.contains(&offset) {
+                    continue;
+                }
+                let key = match &accumulators {
+                    Some(accumulators) => (accumulators[target], 0),
+                    None => (None, target),
+                };
+                alike.entry(key).or_default().push(offset);
+            }
+
+            for mut offsets in alike.into_values() {
+                if states >= MAX_REPAIR_STATES {
+                    truncated = Some(edits.len() + 1);
+                    break 'search;
+                }
+                states += 1;
+                offsets.sort_by_key(|offset| (offset.abs(), *offset));
+                let mut child = edits.clone();
+                child.insert(original_pc, Edit::Retarget(offsets[0]));
+
+                let outcome = match &accumulators {
+                    Some(accumulators) => accumulators[(pc as i32 + offsets[0]) as usize]
+                        .map(|gained| reached[pc].unwrap().wrapping_add(gained))
+                        .ok_or(0),
+                    None => interpreter(&apply_edits(program, &child).0),
+                };
+                if let Ok(accumulator) = outcome {
+                    if child.len() < max_edits {
+                        found_retargets
+                            .entry((edits.clone(), original_pc))
+                            .or_default()
+                            .extend(offsets.iter().copied());
+                    }
+                    repairs.push(EditRepair {
+                        edits: child,
+                        accumulator,
+                        alike: offsets.len(),
+                    });
+                }
+            }
+        }
+    }
+
+    repairs.sort_by(|lhs, rhs| {
+        lhs.edits
+            .len()
+            .cmp(&rhs.edits.len())
+            .then_with(|| lhs.edits.cmp(&rhs.edits))
+    });
+    Ok((repairs, truncated))
+}
+
+const DEBUG_HELP: &str = "\
+commands:
+  s, step [N]         execute N instructions (default 1)
+  c, continue         run until a breakpoint, a loop, a halt or termination
+  b, break SPEC       add breakpoint, SPEC is PC, pc=N or REG<op>N with <op> one of == != < <= > >=
+  d, delete N         delete breakpoint number N
+  i, info             show registers, status and breakpoints
+  l, list [PC [N]]    list N instructions around PC (defaults to current pc and 5)
+  t, trace [N]        show the last N executed instructions (default 10)
+  p, patch PC INSTR   replace the instruction at PC, e.g. `patch 7 nop +0`
+  r, reset            reset registers and trace, keeping patches and breakpoints
+  h, help             show this help
+  q, quit             exit the debugger";
+
+fn describe(vm: &Vm) -> String {
+    match vm.current() {
+        Some(instruction) => format!(
+            "pc={} {} [{}] next: {}",
+            vm.pc,
+            format_registers(&vm.registers),
+            vm.status(),
+            instruction
+        ),
+        None => format!("pc={} {} [{}]", vm.pc, format_registers(&vm.registers), vm.status()),
+    }
+}
+
+fn listing(vm: &Vm, around: i32, radius: i32) -> String {
+    let len = vm.program.len() as i64;
+    let start = (around as i64 - radius as i64).clamp(0, len) as i32;
+    let end = (around as i64 + radius as i64 + 1).clamp(0, len) as i32;
+
+    (start..end)
+        .map(|pc| {
+            let instruction = vm.program[pc as usize];
+            let marker = if pc == vm.pc { "=>" } else { "  " };
+            let breakpoint = if vm.breakpoints.contains(&Breakpoint::Pc(pc)) {
+                "*"
+            } else {
+                " "
+            };
+            let executed = if vm.executed[pc as usize] { "x" } else { " " };
+            format!("{}{}{} {:4}  {}", marker, breakpoint, executed, pc, instruction)
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+fn debug_command(vm: &mut Vm, line: &str) -> Result<Option<String>, String> {
+    let mut tokens = line.split_ascii_whitespace();
+    let command = match tokens.next() {
+        Some(command) => command,
+        None => return Ok(Some(String::new())),
+    };
+    let rest: Vec<&str> = tokens.collect();
+
+    let number = |idx: usize, default: i32| -> Result<i32, String> {
+        rest.get(idx)
+            .map(|token| i32::from_str(token).map_err(|err| format!("Invalid number '{}': {}", token, err)))
+            .unwrap_or(Ok(default))
+    };
+
+    Ok(Some(match command {
+        "s" | "step" => {
+            for _ in 0..number(0, 1)? {
+                if let Status::Terminated | Status::Halted | Status::OutOfBounds = vm.step() {
+                    break;
+                }
+            }
+            describe(vm)
+        }
+        "c" | "continue" => {
+            let status = vm.run();
+            match vm.hit_breakpoint() {
+                Some(breakpoint) if status == Status::Ready => {
+                    format!("hit breakpoint {}\n{}", breakpoint, describe(vm))
+                }
+                _ => describe(vm),
+            }
+        }
+        "b" | "break" => {
+            let breakpoint = Breakpoint::from_str(&rest.join(" "))?;
+            vm.breakpoints.push(breakpoint);
+            format!("breakpoint {}: {}", vm.breakpoints.len() - 1, breakpoint)
+        }
+        "d" | "delete" => {
+            let idx = number(0, -1)?;
+            if idx < 0 || idx as usize >= vm.breakpoints.len() {
+                Err(format!("No such breakpoint: {}", idx))?
+            }
+            format!("deleted breakpoint {}", vm.breakpoints.remove(idx as usize))
+        }
+        "i" | "info" => {
+            let mut info = vec![describe(vm), format!("executed {} instructions", vm.trace.len())];
+            info.extend(
+                vm.breakpoints
+                    .iter()
+                    .enumerate()
+                    .map(|(i, breakpoint)| format!("breakpoint {}: {}", i, breakpoint)),
+            );
+            info.join("\n")
+        }
+        "l" | "list" => listing(vm, number(0, vm.pc)?, number(1, 5)?),
+        "t" | "trace" => {
+            let count = match number(0, 10)? {
+                count if count < 0 => Err(format!("Invalid trace count {}, it must not be negative", count))?,
+                count => usize::max(1, count as usize),
+            };
+            vm.trace[vm.trace.len().saturating_sub(count)..]
+                .iter()
+                .map(|step| {
+                    format!(
+                        "{:4}  {:<14} {}",
+                        step.pc,
+                        step.instruction.to_string(),
+                        format_registers(&step.registers)
+                    )
+                })
+                .collect::<Vec<String>>()
+                .join("\n")
+        }
+        "p" | "patch" => {
+            let pc = number(0, -1)?;
+            if pc < 0 {
+                Err("Usage: patch PC INSTRUCTION".to_string())?
+            }
+            let instruction = Instruction::from_str(&rest[1..].join(" "))?;
+            let old = vm.patch(pc as usize, instruction)?;
+            format!("{:4}  {} -> {}", pc, old, instruction)
+        }
+        "r" | "reset" => {
+            vm.reset();
+            describe(vm)
+        }
+        "h" | "help" => DEBUG_HELP.to_string(),
+        "q" | "quit" => return Ok(None),
+        other => Err(format!("Unknown command '{}', try `help`", other))?,
+    }))
+}
+
+fn program_file<'a>(filename: &'a Path, args: &'a [String]) -> &'a Path {
+    args.iter()
+        .find(|arg| !arg.starts_with("--") && usize::from_str(arg).is_err())
+        .map(Path::new)
+        .unwrap_or(filename)
+}
+
+pub fn debug(filename: &Path, args: &[String]) -> Result<String, String> {
+    let mut vm = Vm::new(parse(program_file(filename, args))?);
+
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+
+    println!("{}", describe(&vm));
+    loop {
+        print!("(dbg) ");
+        io::stdout()
+            .flush()
+            .map_err(|err| format!("Failed to write prompt: {}", err))?;
+
+        let line = match lines.next() {
+            Some(line) => line.map_err(|err| format!("Failed to read command: {}", err))?,
+            None => break,
+        };
+
+        match debug_command(&mut vm, &line) {
+            Ok(Some(output)) if output.is_empty() => (),
+            Ok(Some(output)) => println!("{}", output),
+            Ok(None) => break,
+            Err(err) => println!("error: {}", err),
+        }
+    }
+
+    Ok(format!("Debugger exited with: {}", describe(&vm)))
+}
+
+pub fn run(filename: &Path, args: &[String]) -> Result<String, String> {
+    let mut vm = Vm::new(parse(program_file(filename, args))?);
+    let status = vm.run_until(|_| false);
+
+    Ok(format!(
+        "Program {} after {} instructions: pc={} {}",
+        status,
+        vm.trace.len(),
+        vm.pc,
+        format_registers(&vm.registers)
+    ))
+}
+
+pub fn disasm(filename: &Path, args: &[String]) -> Result<String, String> {
+    let program = parse(program_file(filename, args))?;
+    Ok(disassemble(&program, args.iter().any(|arg| arg == "--labels")))
+}
+
+pub fn repair(filename: &Path, args: &[String]) -> Result<String, String> {
+    let program = parse(program_file(filename, args))?;
+    let max_edits = match args.iter().find_map(|arg| usize::from_str(arg).ok()) {
+        Some(0) => Err("The number of edits must be positive".to_string())?,
+        Some(k) => k,
+        None => 2,
+    };
+
+    let (repairs, truncated) = find_repairs(&program, max_edits)?;
+    let all = args.iter().any(|arg| arg == "--all");
+
+    // grouped by the kinds of edit made, with only the first few of each kind unless all were asked for
+    let mut kinds: BTreeMap<(usize, Vec<&str>), Vec<&EditRepair>> = BTreeMap::new();
+    for repair in repairs.iter() {
+        let mut kind: Vec<&str> = repair.edits.values().map(Edit::name).collect();
+        kind.sort_unstable();
+        kinds.entry((kind.len(), kind)).or_default().push(repair);
+    }
+
+    let mut lines = Vec::new();
+    for ((edit_count, kind), repairs) in kinds.iter() {
+        lines.push(format!(
+            "{} edit(s), {}: {} repair(s)",
+            edit_count,
+            kind.join(" + "),
+            repairs.len()
+        ));
+        let shown = if all { repairs.len() } else { REPAIR_EXAMPLES };
+        for repair in repairs.iter().take(shown) {
+            let mut line = format!("    acc={}: {}", repair.accumulator, describe_edits(&program, &repair.edits));
+            if repair.alike > 1 {
+                line.push_str(&format!(" (or {} other targets with the same acc)", repair.alike - 1));
+            }
+            lines.push(line);
+        }
+        if repairs.len() > shown {
+            lines.push(format!("    ... {} more, --all lists them", repairs.len() - shown));
+        }
+    }
+
+    if let Some(edit_count) = truncated {
+        lines.push(format!(
+            "search stopped after {} states, repairs with {} or more edit(s) may be missing",
+            MAX_REPAIR_STATES, edit_count
+        ));
+    }
+    lines.push(format!(
+        "Minimal repairs with at most {} edit(s): {} ({} counting every retarget target)",
+        max_edits,
+        repairs.len(),
+        repairs.iter().map(|repair| repair.alike).sum::<usize>()
+    ));
+    Ok(lines.join("\n"))
+}
+
 pub fn part01(filename: &Path) -> Result<String, String> {
     let program = parse(filename)?;
 
@@ -102,8 +1354,9 @@ pub fn part01(filename: &Path) -> Result<String, String> {
 
 pub fn part02(filename: &Path) -> Result<String, String> {
     let program = parse(filename)?;
+    let repair = find_correction(&program)?;
     Ok(format!(
-        "Found correction, accumulator after termination is: {}",
-        find_correction(&program)?
+        "Found correction at pc {} ({} -> {}), accumulator after termination is: {}",
+        repair.pc, repair.original, repair.replacement, repair.accumulator
     ))
 }
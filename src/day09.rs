@@ -1,9 +1,10 @@
-use std::fs;
+use std::collections::{HashMap, VecDeque};
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, Write};
 use std::path::Path;
 use std::str::FromStr;
 
-use itertools::Itertools;
-use std::collections::VecDeque;
+const PREAMBLE: usize = 25;
 
 fn parse(filename: &Path) -> Result<Vec<i64>, String> {
     fs::read_to_string(filename)
@@ -13,53 +14,239 @@ fn parse(filename: &Path) -> Result<Vec<i64>, String> {
         .collect()
 }
 
-fn find_trailing_sum(data: &Vec<i64>) -> Option<i64> {
-    data.windows(26)
-        .find(|window| {
-            window[..25]
-                .iter()
-                .tuple_combinations()
-                .all(|(first, last)| first + last != window[25])
+// the last `preamble` numbers, both in order and as a multiset for constant time lookups
+struct Window {
+    preamble: usize,
+    numbers: VecDeque<i64>,
+    counts: HashMap<i64, usize>,
+}
+
+impl Window {
+    fn new(preamble: usize) -> Self {
+        Window {
+            preamble,
+            numbers: VecDeque::with_capacity(preamble + 1),
+            counts: HashMap::with_capacity(preamble + 1),
+        }
+    }
+
+    // whether two numbers at different positions in the window sum to `target`
+    fn has_pair_sum(&self, target: i64) -> bool {
+        self.numbers.iter().any(|&number| match target.checked_sub(number) {
+            Some(other) if other == number => self.counts.get(&other).copied().unwrap_or(0) >= 2,
+            Some(other) => self.counts.contains_key(&other),
+            None => false,
         })
-        .map(|window| window[25])
+    }
+
+    // slides `number` into the window, `None` while the preamble is still filling up
+    fn push(&mut self, number: i64) -> Option<bool> {
+        let valid = if self.numbers.len() < self.preamble {
+            None
+        } else {
+            Some(self.has_pair_sum(number))
+        };
+
+        self.numbers.push_back(number);
+        *self.counts.entry(number).or_default() += 1;
+        if self.numbers.len() > self.preamble {
+            let oldest = self.numbers.pop_front().unwrap();
+            let count = self.counts.get_mut(&oldest).unwrap();
+            *count -= 1;
+            if *count == 0 {
+                self.counts.remove(&oldest);
+            }
+        }
+
+        valid
+    }
+}
+
+// every (index, number) that is not the sum of two of the `preamble` numbers before it
+fn find_invalid(data: &[i64], preamble: usize) -> Vec<(usize, i64)> {
+    let mut window = Window::new(preamble);
+    data.iter()
+        .enumerate()
+        .filter(|(_, &number)| window.push(number) == Some(false))
+        .map(|(idx, &number)| (idx, number))
+        .collect()
+}
+
+// the first contiguous range of at least two numbers summing to `target`, as (start, end) with `end` exclusive
+fn find_contiguous_sum(data: &[i64], target: i64) -> Option<(usize, usize)> {
+    let mut prefix = Vec::with_capacity(data.len() + 1);
+    prefix.push(0i128);
+    for &number in data {
+        prefix.push(prefix.last().unwrap() + number as i128);
+    }
+
+    // earliest index of every prefix sum that ends at least two numbers before the current end
+    let mut starts: HashMap<i128, usize> = HashMap::new();
+    for end in 2..prefix.len() {
+        starts.entry(prefix[end - 2]).or_insert(end - 2);
+        if let Some(&start) = starts.get(&(prefix[end] - target as i128)) {
+            return Some((start, end));
+        }
+    }
+
+    None
+}
+
+fn find_encryption_weakness(data: &[i64], target: i64) -> Option<i64> {
+    find_contiguous_sum(data, target).map(|(start, end)| {
+        let range = &data[start..end];
+        range.iter().min().unwrap() + range.iter().max().unwrap()
+    })
 }
 
-fn find_encryption_weakness(data: &Vec<i64>, target: i64) -> Option<i64> {
-    let mut data = data.iter();
-    let mut buffer: VecDeque<i64> = VecDeque::new();
+fn parse_preamble(preamble: &str) -> Result<usize, String> {
+    usize::from_str(preamble).map_err(|err| format!("Invalid preamble '{}': {}", preamble, err))
+}
+
+pub fn validate(filename: &Path, args: &[String]) -> Result<String, String> {
+    let preamble = match args {
+        [] => PREAMBLE,
+        [preamble] => parse_preamble(preamble)?,
+        _ => Err("Usage: validate 9 [PREAMBLE]".to_string())?,
+    };
+    let data = parse(filename)?;
+
+    let invalid = find_invalid(&data, preamble);
+    let mut lines: Vec<String> = invalid
+        .iter()
+        .map(|(idx, number)| format!("line {}: {}", idx + 1, number))
+        .collect();
+    lines.push(format!(
+        "Invalid numbers with preamble {}: {} (total: {})",
+        preamble,
+        invalid.len(),
+        data.len()
+    ));
+    if let Some((_, first)) = invalid.first() {
+        lines.push(match find_contiguous_sum(&data, *first) {
+            Some((start, end)) => format!(
+                "Encryption weakness for {}: lines {}-{}, {}",
+                first,
+                start + 1,
+                end,
+                find_encryption_weakness(&data, *first).unwrap()
+            ),
+            None => format!("No contiguous range sums to {}", first),
+        });
+    }
+
+    Ok(lines.join("\n"))
+}
 
+// longer than any i64, so a runaway token is rejected instead of buffered
+const MAX_TOKEN: usize = 32;
+
+// reads the next whitespace separated token into `token` without holding more of the input than that, returns
+// false at the end of the input
+fn next_token(reader: &mut impl BufRead, token: &mut Vec<u8>) -> Result<bool, String> {
+    token.clear();
     loop {
-        let sum: i64 = buffer.iter().sum();
-        if sum == target {
-            break Some(*buffer.iter().min().unwrap() + *buffer.iter().max().unwrap());
-        } else if sum > target {
-            if buffer.is_empty() {
-                break None;
-            } else {
-                buffer.pop_front();
+        let buffer = reader
+            .fill_buf()
+            .map_err(|err| format!("Failed to read input: {}", err))?;
+        if buffer.is_empty() {
+            return Ok(!token.is_empty());
+        }
+
+        let mut used = 0;
+        let mut complete = false;
+        for &byte in buffer {
+            used += 1;
+            if !byte.is_ascii_whitespace() {
+                token.push(byte);
+            } else if !token.is_empty() {
+                complete = true;
+                break;
             }
-        } else {
-            let next = data.next();
-            if next.is_none() {
-                break None;
-            } else {
-                buffer.push_back(*next.unwrap())
+            if token.len() > MAX_TOKEN {
+                Err(format!(
+                    "Failed to parse number starting '{}...': too long",
+                    String::from_utf8_lossy(&token[..MAX_TOKEN])
+                ))?
             }
         }
+        reader.consume(used);
+        if complete {
+            return Ok(true);
+        }
     }
 }
 
+// checks whitespace separated numbers from `reader` as they arrive, writing every invalid one to `out`
+// as `POSITION NUMBER`, returns (numbers checked, invalid numbers)
+fn stream_invalid(mut reader: impl BufRead, out: &mut impl Write, preamble: usize) -> Result<(usize, usize), String> {
+    let mut window = Window::new(preamble);
+    let mut position = 0;
+    let mut invalid = 0;
+    let mut token = Vec::with_capacity(MAX_TOKEN + 1);
+
+    while next_token(&mut reader, &mut token)? {
+        position += 1;
+        let text = String::from_utf8_lossy(&token);
+        let number =
+            i64::from_str(&text).map_err(|err| format!("Failed to parse number {} '{}': {}", position, text, err))?;
+        if window.push(number) == Some(false) {
+            invalid += 1;
+            match writeln!(out, "{} {}", position, number).and_then(|_| out.flush()) {
+                Ok(()) => (),
+                // the reading end of a pipe has seen enough
+                Err(err) if err.kind() == io::ErrorKind::BrokenPipe => return Ok((position, invalid)),
+                Err(err) => Err(format!("Failed to write output: {}", err))?,
+            }
+        }
+    }
+
+    Ok((position, invalid))
+}
+
+pub fn stream(filename: &Path, args: &[String]) -> Result<String, String> {
+    let usage = "Usage: stream 9 [PREAMBLE [FILE|-]]";
+    let (preamble, source) = match args {
+        [] => (PREAMBLE, None),
+        [preamble] => (parse_preamble(preamble)?, None),
+        [preamble, source] => (parse_preamble(preamble)?, Some(source.as_str())),
+        _ => Err(usage.to_string())?,
+    };
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+
+    let (checked, invalid) = match source {
+        Some("-") => stream_invalid(io::stdin().lock(), &mut out, preamble)?,
+        source => {
+            let path = source.map_or(filename, Path::new);
+            let file = File::open(path).map_err(|err| format!("Failed to open {}: {}", path.display(), err))?;
+            stream_invalid(BufReader::new(file), &mut out, preamble)?
+        }
+    };
+
+    // keep stdout to the invalid numbers so the output can be piped into other tools
+    eprintln!(
+        "Checked {} numbers with preamble {}: {} invalid",
+        checked, preamble, invalid
+    );
+    Ok(String::new())
+}
+
 pub fn part01(filename: &Path) -> Result<String, String> {
     let data = parse(filename)?;
-    match find_trailing_sum(&data) {
-        Some(v) => Ok(v.to_string()),
+    match find_invalid(&data, PREAMBLE).first() {
+        Some((_, v)) => Ok(v.to_string()),
         None => Err("Not found".to_string()),
     }
 }
 
 pub fn part02(filename: &Path) -> Result<String, String> {
     let data = parse(filename)?;
-    match find_encryption_weakness(&data, find_trailing_sum(&data).unwrap()) {
+    let target = match find_invalid(&data, PREAMBLE).first() {
+        Some((_, v)) => *v,
+        None => Err("No invalid number to find the weakness for".to_string())?,
+    };
+    match find_encryption_weakness(&data, target) {
         Some(v) => Ok(v.to_string()),
         None => Err("Not found".to_string()),
     }
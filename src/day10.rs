@@ -1,62 +1,301 @@
+use std::collections::{BTreeMap, BTreeSet};
 use std::fs;
 use std::path::Path;
 use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
 
-fn parse(filename: &Path) -> Result<Vec<usize>, String> {
+use num_bigint::BigUint;
+use num_traits::{One, Zero};
+
+fn parse(filename: &Path) -> Result<Vec<u64>, String> {
     let mut numbers = fs::read_to_string(filename)
         .map_err(|err| format!("Failed to read data for day 10: {}", err))?
         .split_ascii_whitespace()
-        .map(|v| usize::from_str(v).map_err(|err| format!("Failed to parse number: {}", err)))
-        .collect::<Result<Vec<usize>, _>>()?;
+        .map(|v| u64::from_str(v).map_err(|err| format!("Failed to parse number: {}", err)))
+        .collect::<Result<Vec<u64>, _>>()?;
 
-    numbers.sort();
+    numbers.sort_unstable();
     Ok(numbers)
 }
 
-pub fn part01(filename: &Path) -> Result<String, String> {
-    let data = parse(filename)?;
+// joltages from the outlet (0) through every adapter to the device, which is rated the largest allowed
+// difference above the highest adapter
+struct Chain {
+    joltages: Vec<u64>,
+    allowed: BTreeSet<u64>,
+}
+
+impl Chain {
+    fn new(adapters: &[u64], allowed: BTreeSet<u64>) -> Result<Self, String> {
+        let max_diff = *allowed
+            .iter()
+            .next_back()
+            .ok_or("At least one allowed difference is needed")?;
+
+        let mut joltages = Vec::with_capacity(adapters.len() + 2);
+        joltages.push(0);
+        joltages.extend(adapters);
+        let highest = *adapters.last().unwrap_or(&0);
+        joltages.push(highest.checked_add(max_diff).ok_or(format!(
+            "The device rating overflows: {} above the highest adapter {}",
+            max_diff, highest
+        ))?);
+
+        Ok(Chain { joltages, allowed })
+    }
+
+    fn device(&self) -> usize {
+        self.joltages.len() - 1
+    }
+
+    fn describe(&self, idx: usize) -> String {
+        if idx == 0 {
+            "the outlet (0)".to_string()
+        } else if idx == self.device() {
+            format!("the device ({})", self.joltages[idx])
+        } else {
+            format!("adapter {}", self.joltages[idx])
+        }
+    }
+
+    fn describe_allowed(&self) -> String {
+        self.allowed
+            .iter()
+            .map(u64::to_string)
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
+
+    // indices of the joltages that can be plugged into `to`
+    fn predecessors(&self, to: usize) -> impl Iterator<Item = usize> + '_ {
+        let max_diff = *self.allowed.iter().next_back().unwrap();
+        let joltage = self.joltages[to];
+        (0..to)
+            .rev()
+            .take_while(move |&from| joltage - self.joltages[from] <= max_diff)
+            .filter(move |&from| self.allowed.contains(&(joltage - self.joltages[from])))
+    }
+
+    // number of differences of each size when every adapter is used
+    fn distribution(&self) -> Result<BTreeMap<u64, usize>, String> {
+        let mut distribution = BTreeMap::new();
+        for idx in 1..self.joltages.len() {
+            let diff = self.joltages[idx] - self.joltages[idx - 1];
+            if !self.allowed.contains(&diff) {
+                Err(format!(
+                    "The chain breaks between {} and {}: difference {} is not one of {}",
+                    self.describe(idx - 1),
+                    self.describe(idx),
+                    diff,
+                    self.describe_allowed()
+                ))?
+            }
+            *distribution.entry(diff).or_insert(0) += 1;
+        }
+        Ok(distribution)
+    }
+
+    // number of arrangements from the outlet ending at each index
+    fn ways(&self) -> Result<Vec<BigUint>, String> {
+        let mut ways: Vec<BigUint> = vec![BigUint::zero(); self.joltages.len()];
+        ways[0] = BigUint::one();
+        for to in 1..self.joltages.len() {
+            ways[to] = self.predecessors(to).map(|from| &ways[from]).sum();
+        }
+
+        if ways[self.device()].is_zero() {
+            let reachable = (0..self.device()).rev().find(|&idx| !ways[idx].is_zero()).unwrap();
+            Err(format!(
+                "The chain breaks after {}: {} is out of reach with differences {}",
+                self.describe(reachable),
+                self.describe(reachable + 1),
+                self.describe_allowed()
+            ))?
+        }
 
-    // one and three diff, and previous joltages
-    let mut d1 = 0;
-    let mut d3 = 1; // laptop is 3 higher than it's charger
-    let mut pj = 0; // plane adaptor is zero
+        Ok(ways)
+    }
+
+    fn arrangement(&self, path: &[usize]) -> String {
+        path.iter()
+            .filter(|&&idx| idx != 0 && idx != self.device())
+            .map(|&idx| self.joltages[idx].to_string())
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+
+    // the first `limit` arrangements, each listing the adapters used from lowest to highest
+    fn enumerate(&self, ways: &[BigUint], limit: usize) -> Vec<String> {
+        let mut arrangements = Vec::new();
+        // partial paths walking backwards from the device
+        let mut stack = vec![vec![self.device()]];
+        while let Some(path) = stack.pop() {
+            if arrangements.len() >= limit {
+                break;
+            }
 
-    for j in data.iter() {
-        if j - pj == 1 {
-            d1 += 1;
-        } else if j - pj == 3 {
-            d3 += 1;
+            let last = *path.last().unwrap();
+            if last == 0 {
+                arrangements.push(self.arrangement(&path.iter().rev().cloned().collect::<Vec<usize>>()));
+                continue;
+            }
+            for from in self.predecessors(last).filter(|&from| !ways[from].is_zero()) {
+                let mut next = path.clone();
+                next.push(from);
+                stack.push(next);
+            }
         }
-        pj = *j;
+        arrangements
     }
 
-    Ok(format!("Product of 1-diff and 3-diff joltages: {}", d1 * d3))
+    // an arrangement drawn uniformly from all of them, picking each plug weighted by its arrangement count
+    fn sample(&self, ways: &[BigUint], rng: &mut XorShift) -> String {
+        let mut path = vec![self.device()];
+        let mut current = self.device();
+        while current != 0 {
+            let mut pick = rng.below(&ways[current]);
+            for from in self.predecessors(current) {
+                if pick < ways[from] {
+                    current = from;
+                    break;
+                }
+                pick -= &ways[from];
+            }
+            path.push(current);
+        }
+        path.reverse();
+        self.arrangement(&path)
+    }
 }
 
-pub fn part02(filename: &Path) -> Result<String, String> {
-    let data = parse(filename)?;
-
-    // previous 3, joltage and combinations so far
-    let mut p3: (usize, usize) = (0, 0);
-    let mut p2: (usize, usize) = (0, 0);
-    let mut p1: (usize, usize) = (0, 1); // represents connecting to the outlet
-
-    for i in data.iter() {
-        // compute possible chargers the current can connect to
-        let v = if i - p3.0 <= 3 {
-            p3.1 + p2.1 + p1.1
-        } else if i - p2.0 <= 3 {
-            p2.1 + p1.1
-        } else if i - p1.0 <= 3 {
-            p1.1
-        } else {
-            0
-        };
+struct XorShift(u64);
+
+impl XorShift {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    // uniform in `0..bound` by rejection sampling
+    fn below(&mut self, bound: &BigUint) -> BigUint {
+        let bits = bound.bits();
+        loop {
+            let words = bits.div_ceil(32);
+            let digits: Vec<u32> = (0..words).map(|_| self.next() as u32).collect();
+            let candidate = BigUint::from_slice(&digits) >> (words * 32 - bits);
+            if &candidate < bound {
+                break candidate;
+            }
+        }
+    }
+}
+
+struct Options {
+    allowed: BTreeSet<u64>,
+    count: usize,
+    seed: u64,
+}
+
+// `diffs=A,B,..`, `count=N` and `seed=S`, all optional
+fn parse_options(args: &[String], command: &str) -> Result<Options, String> {
+    let mut options = Options {
+        allowed: vec![1, 2, 3].into_iter().collect(),
+        count: 10,
+        seed: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|time| time.as_nanos() as u64)
+            .unwrap_or(0),
+    };
 
-        p3 = p2;
-        p2 = p1;
-        p1 = (*i, v);
+    for arg in args {
+        let (key, value) = match arg.find('=') {
+            Some(idx) => (&arg[..idx], &arg[idx + 1..]),
+            None => Err(format!("Usage: {} 10 [diffs=A,B,..] [count=N] [seed=S]", command))?,
+        };
+        match key {
+            "diffs" => {
+                options.allowed = value
+                    .split(',')
+                    .map(|diff| match u64::from_str(diff) {
+                        Ok(0) => Err("Allowed differences must be positive".to_string()),
+                        Ok(diff) => Ok(diff),
+                        Err(err) => Err(format!("Invalid difference '{}': {}", diff, err)),
+                    })
+                    .collect::<Result<BTreeSet<u64>, String>>()?
+            }
+            "count" => {
+                options.count = usize::from_str(value).map_err(|err| format!("Invalid count '{}': {}", value, err))?
+            }
+            "seed" => {
+                options.seed = u64::from_str(value).map_err(|err| format!("Invalid seed '{}': {}", value, err))?
+            }
+            _ => Err(format!("Unknown option '{}' for {}", key, command))?,
+        }
     }
+    // xorshift never leaves zero
+    options.seed = options.seed.max(1);
+
+    Ok(options)
+}
+
+pub fn chain(filename: &Path, args: &[String]) -> Result<String, String> {
+    let options = parse_options(args, "chain")?;
+    let chain = Chain::new(&parse(filename)?, options.allowed)?;
+
+    let mut lines: Vec<String> = chain
+        .distribution()?
+        .iter()
+        .map(|(diff, count)| format!("{}-jolt differences: {}", diff, count))
+        .collect();
+    lines.push(format!(
+        "Total distinct charger combinations: {}",
+        chain.ways()?[chain.device()]
+    ));
+
+    Ok(lines.join("\n"))
+}
+
+pub fn arrangements(filename: &Path, args: &[String]) -> Result<String, String> {
+    let options = parse_options(args, "arrangements")?;
+    let chain = Chain::new(&parse(filename)?, options.allowed)?;
+    let ways = chain.ways()?;
+
+    Ok(chain.enumerate(&ways, options.count).join("\n"))
+}
+
+pub fn sample(filename: &Path, args: &[String]) -> Result<String, String> {
+    let options = parse_options(args, "sample")?;
+    let chain = Chain::new(&parse(filename)?, options.allowed)?;
+    let ways = chain.ways()?;
+
+    let mut rng = XorShift(options.seed);
+    Ok((0..options.count)
+        .map(|_| chain.sample(&ways, &mut rng))
+        .collect::<Vec<String>>()
+        .join("\n"))
+}
+
+fn default_chain(filename: &Path) -> Result<Chain, String> {
+    Chain::new(&parse(filename)?, vec![1, 2, 3].into_iter().collect())
+}
+
+pub fn part01(filename: &Path) -> Result<String, String> {
+    let distribution = default_chain(filename)?.distribution()?;
+    let count = |diff| distribution.get(&diff).cloned().unwrap_or(0);
+
+    Ok(format!(
+        "Product of 1-diff and 3-diff joltages: {}",
+        count(1) * count(3)
+    ))
+}
+
+pub fn part02(filename: &Path) -> Result<String, String> {
+    let chain = default_chain(filename)?;
 
-    Ok(format!("Total distinct charger combinations: {}", p1.1))
+    Ok(format!(
+        "Total distinct charger combinations: {}",
+        chain.ways()?[chain.device()]
+    ))
 }
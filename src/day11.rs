@@ -1,12 +1,23 @@
+use std::collections::HashMap;
 use std::convert::TryFrom;
+use std::fmt;
 use std::fs;
 use std::path::Path;
+use std::str::FromStr;
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crossterm::style::Color;
+
+use crate::render::{self, Frame, Palette, Recorder};
+use crate::terminal::{self, LiveView};
 
 const FLOOR: char = '.';
 const EMPTY: char = 'L';
 const TAKEN: char = '#';
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 enum Cell {
     Floor,
     Empty,
@@ -141,16 +152,92 @@ impl Grid {
         }
     }
 
-    pub fn simulate<FR, FT>(&mut self, rule: FR, terminate: FT)
+    // cells within `radius` steps in any direction, including diagonally
+    pub fn within_radius(&self, origin: &Point, radius: isize) -> Vec<Option<&Cell>> {
+        (-radius..=radius)
+            .flat_map(|dy| (-radius..=radius).map(move |dx| (dx, dy)))
+            .filter(|&(dx, dy)| dx != 0 || dy != 0)
+            .map(|offset| self.get_offset(origin, &offset.into()))
+            .collect()
+    }
+
+    pub fn neighbours(&self, origin: &Point, neighbourhood: Neighbourhood) -> Vec<Option<&Cell>> {
+        match neighbourhood {
+            Neighbourhood::Adjacent => self.adjacent_next(origin).to_vec(),
+            Neighbourhood::LineOfSight => self.adjacent_nearest(origin, |&cell| cell != Cell::Floor).to_vec(),
+            Neighbourhood::Radius(radius) => self.within_radius(origin, radius as isize),
+        }
+    }
+
+    // indices of the seats in the neighbourhood, floor never changes so it is left out
+    pub fn neighbour_seats(&self, origin: &Point, neighbourhood: Neighbourhood) -> Vec<usize> {
+        let index = |x: isize, y: isize| {
+            if x >= 0 && y >= 0 && (x as usize) < self.width && (y as usize) < self.height {
+                Some(y as usize * self.width + x as usize)
+            } else {
+                None
+            }
+        };
+        let radius = match neighbourhood {
+            Neighbourhood::Radius(radius) => radius as isize,
+            _ => 1,
+        };
+
+        let mut seats = Vec::new();
+        for dy in -radius..=radius {
+            for dx in -radius..=radius {
+                if dx == 0 && dy == 0 {
+                    continue;
+                }
+                let (mut x, mut y) = (origin.x + dx, origin.y + dy);
+                while let Some(idx) = index(x, y) {
+                    if self.cells[idx] != Cell::Floor {
+                        seats.push(idx);
+                        break;
+                    } else if neighbourhood != Neighbourhood::LineOfSight {
+                        break;
+                    }
+                    x += dx;
+                    y += dy;
+                }
+            }
+        }
+        seats
+    }
+
+    // applies `rule` to every cell until the grid repeats an earlier layout or `limit` rounds have passed,
+    // calling `observe` with the round number and grid before the first round and after every round, stopping
+    // early when it returns false
+    pub fn simulate<FR, FO>(&mut self, rule: FR, limit: usize, mut observe: FO) -> Outcome
     where
         FR: Fn(&Self, Point, &Cell) -> Cell,
-        FT: Fn(&Self, &Self) -> bool,
+        FO: FnMut(usize, &Self) -> bool,
     {
+        let mut seen: HashMap<Vec<Cell>, usize> = HashMap::new();
         let mut next = self.clone();
+        let mut round = 0;
+        if !observe(round, self) {
+            return Outcome::Stopped { rounds: round };
+        }
+
         loop {
+            if let Some(&start) = seen.get(&self.cells) {
+                break if round - start == 1 {
+                    Outcome::Stable { rounds: start }
+                } else {
+                    Outcome::Oscillating {
+                        start,
+                        period: round - start,
+                    }
+                };
+            } else if round == limit {
+                break Outcome::Unsettled { rounds: round };
+            }
+            seen.insert(self.cells.clone(), round);
+
             for (i, cell) in self.cells.iter().enumerate() {
                 next.cells[i] = rule(
-                    &self,
+                    self,
                     Point {
                         x: (i % self.width) as isize,
                         y: (i / self.width) as isize,
@@ -160,17 +247,304 @@ impl Grid {
             }
 
             std::mem::swap(&mut self.cells, &mut next.cells);
-            if terminate(self, &next) {
-                break;
+            round += 1;
+            if !observe(round, self) {
+                break Outcome::Stopped { rounds: round };
             }
         }
     }
 
+    // floor, empty and taken seats as palette indices 0, 1 and 2
+    pub fn frame(&self, scale: usize) -> Frame {
+        let mut frame = Frame::new(self.width * scale, self.height * scale);
+        for (i, cell) in self.cells.iter().enumerate() {
+            let colour = match cell {
+                Cell::Floor => 0,
+                Cell::Empty => 1,
+                Cell::Taken => 2,
+            };
+            frame.cell(i % self.width, i / self.width, scale, colour);
+        }
+        frame
+    }
+
+    // rows of cell characters coloured for a terminal
+    pub fn styled(&self) -> Vec<Vec<(char, Color)>> {
+        self.cells
+            .chunks(self.width)
+            .map(|row| {
+                row.iter()
+                    .map(|cell| match cell {
+                        Cell::Floor => (FLOOR, Color::DarkGrey),
+                        Cell::Empty => (EMPTY, Color::Green),
+                        Cell::Taken => (TAKEN, Color::Red),
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
     pub fn cell_count(&self, cell: &Cell) -> usize {
         self.cells.iter().filter(|&c| c == cell).count()
     }
 }
 
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Neighbourhood {
+    Adjacent,
+    LineOfSight,
+    Radius(usize),
+}
+
+impl FromStr for Neighbourhood {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "adjacent" => Ok(Neighbourhood::Adjacent),
+            "sight" => Ok(Neighbourhood::LineOfSight),
+            _ if s.starts_with("radius:") => match usize::from_str(&s["radius:".len()..]) {
+                Ok(0) => Err("The neighbourhood radius must be positive".to_string()),
+                Ok(radius) => Ok(Neighbourhood::Radius(radius)),
+                Err(err) => Err(format!("Invalid neighbourhood '{}': {}", s, err)),
+            },
+            _ => Err(format!(
+                "Unknown neighbourhood '{}', expected adjacent, sight or radius:R",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Neighbourhood {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Neighbourhood::Adjacent => write!(f, "adjacent"),
+            Neighbourhood::LineOfSight => write!(f, "line-of-sight"),
+            Neighbourhood::Radius(radius) => write!(f, "radius {}", radius),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+struct Rules {
+    neighbourhood: Neighbourhood,
+    // a taken seat is left when at least this many neighbours are taken
+    crowding: usize,
+    // an empty seat is taken when at most this many neighbours are taken
+    empty: usize,
+}
+
+impl Rules {
+    fn part1() -> Self {
+        Rules {
+            neighbourhood: Neighbourhood::Adjacent,
+            crowding: 4,
+            empty: 0,
+        }
+    }
+
+    fn part2() -> Self {
+        Rules {
+            neighbourhood: Neighbourhood::LineOfSight,
+            crowding: 5,
+            empty: 0,
+        }
+    }
+
+    fn apply(&self, grid: &Grid, at: Point, cell: &Cell) -> Cell {
+        if *cell == Cell::Floor {
+            return Cell::Floor;
+        }
+
+        let taken = grid
+            .neighbours(&at, self.neighbourhood)
+            .iter()
+            .filter(|&&cell| cell == Some(&Cell::Taken))
+            .count();
+        match cell {
+            Cell::Empty if taken <= self.empty => Cell::Taken,
+            Cell::Taken if taken >= self.crowding => Cell::Empty,
+            _ => *cell,
+        }
+    }
+}
+
+impl fmt::Display for Rules {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} neighbourhood, leave with at least {} taken, sit with at most {} taken",
+            self.neighbourhood, self.crowding, self.empty
+        )
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Outcome {
+    // no seat changes after this many rounds
+    Stable { rounds: usize },
+    // the layout after round `start` repeats every `period` rounds
+    Oscillating { start: usize, period: usize },
+    // gave up after this many rounds without a repeated layout
+    Unsettled { rounds: usize },
+    // the observer stopped the simulation after this many rounds
+    Stopped { rounds: usize },
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Stable { rounds } => write!(f, "stable after {} rounds", rounds),
+            Outcome::Oscillating { start, period } => {
+                write!(f, "oscillating with period {} from round {}", period, start)
+            }
+            Outcome::Unsettled { rounds } => write!(f, "not settled after {} rounds", rounds),
+            Outcome::Stopped { rounds } => write!(f, "stopped after {} rounds", rounds),
+        }
+    }
+}
+
+// seats and who each seat can see, stored as one flat neighbour list with per-seat offsets into it
+struct SeatGraph {
+    cells: Vec<usize>,
+    offsets: Vec<usize>,
+    neighbours: Vec<u32>,
+}
+
+impl SeatGraph {
+    fn new(grid: &Grid, neighbourhood: Neighbourhood) -> Self {
+        let cells: Vec<usize> = (0..grid.cells.len())
+            .filter(|&idx| grid.cells[idx] != Cell::Floor)
+            .collect();
+        let mut seat_of = vec![u32::MAX; grid.cells.len()];
+        for (seat, &idx) in cells.iter().enumerate() {
+            seat_of[idx] = seat as u32;
+        }
+
+        let mut offsets = Vec::with_capacity(cells.len() + 1);
+        let mut neighbours = Vec::new();
+        offsets.push(0);
+        for &idx in cells.iter() {
+            let at = Point {
+                x: (idx % grid.width) as isize,
+                y: (idx / grid.width) as isize,
+            };
+            neighbours.extend(
+                grid.neighbour_seats(&at, neighbourhood)
+                    .into_iter()
+                    .map(|neighbour| seat_of[neighbour]),
+            );
+            offsets.push(neighbours.len());
+        }
+
+        SeatGraph {
+            cells,
+            offsets,
+            neighbours,
+        }
+    }
+
+    // updates the seats starting at `first` into `next`, returning whether any of them changed
+    fn update(&self, rules: Rules, taken: &[bool], next: &mut [bool], first: usize) -> bool {
+        let mut changed = false;
+        for (seat, slot) in (first..).zip(next.iter_mut()) {
+            let count = self.neighbours[self.offsets[seat]..self.offsets[seat + 1]]
+                .iter()
+                .filter(|&&neighbour| taken[neighbour as usize])
+                .count();
+            *slot = if taken[seat] {
+                count < rules.crowding
+            } else {
+                count <= rules.empty
+            };
+            changed |= *slot != taken[seat];
+        }
+        changed
+    }
+
+    // runs the same rounds as `Grid::simulate` with `Rules::apply` over the seats only, with the seats split
+    // between `threads` workers that live for the whole simulation
+    fn simulate(&self, grid: &mut Grid, rules: Rules, threads: usize, limit: usize) -> Outcome {
+        let taken: Vec<bool> = self.cells.iter().map(|&idx| grid.cells[idx] == Cell::Taken).collect();
+
+        let (outcome, taken) = if threads <= 1 || taken.len() < 2 {
+            settle(taken, limit, |taken, next| self.update(rules, taken, next, 0))
+        } else {
+            let chunk = taken.len().div_ceil(threads);
+            thread::scope(|scope| {
+                let (results, finished) = mpsc::channel();
+                let workers: Vec<mpsc::Sender<Arc<Vec<bool>>>> = (0..taken.len())
+                    .step_by(chunk)
+                    .map(|first| {
+                        let (work, rounds) = mpsc::channel::<Arc<Vec<bool>>>();
+                        let results = results.clone();
+                        scope.spawn(move || {
+                            for taken in rounds {
+                                let mut next = vec![false; chunk.min(taken.len() - first)];
+                                let changed = self.update(rules, &taken, &mut next, first);
+                                if results.send((first, next, changed)).is_err() {
+                                    break;
+                                }
+                            }
+                        });
+                        work
+                    })
+                    .collect();
+
+                // workers stop once their senders are dropped at the end of the scope
+                settle(taken, limit, |taken, next| {
+                    let taken = Arc::new(taken.to_vec());
+                    for worker in workers.iter() {
+                        worker.send(Arc::clone(&taken)).unwrap();
+                    }
+                    let mut changed = false;
+                    for (first, part, part_changed) in finished.iter().take(workers.len()) {
+                        next[first..first + part.len()].copy_from_slice(&part);
+                        changed |= part_changed;
+                    }
+                    changed
+                })
+            })
+        };
+
+        for (&idx, &taken) in self.cells.iter().zip(taken.iter()) {
+            grid.cells[idx] = if taken { Cell::Taken } else { Cell::Empty };
+        }
+        outcome
+    }
+}
+
+// repeats `round`, which fills the next seats and returns whether any changed, until the seats settle
+fn settle<F>(mut taken: Vec<bool>, limit: usize, mut round: F) -> (Outcome, Vec<bool>)
+where
+    F: FnMut(&[bool], &mut [bool]) -> bool,
+{
+    let mut next = taken.clone();
+    let mut seen: HashMap<Vec<bool>, usize> = HashMap::new();
+    let mut rounds = 0;
+
+    let outcome = loop {
+        if rounds == limit {
+            break Outcome::Unsettled { rounds };
+        }
+        if !round(&taken, &mut next) {
+            break Outcome::Stable { rounds };
+        }
+        seen.insert(taken.clone(), rounds);
+        std::mem::swap(&mut taken, &mut next);
+        rounds += 1;
+        if let Some(&start) = seen.get(&taken) {
+            break Outcome::Oscillating {
+                start,
+                period: rounds - start,
+            };
+        }
+    };
+
+    (outcome, taken)
+}
+
 fn parse(filename: &Path) -> Result<Grid, String> {
     let data = fs::read_to_string(filename)
         .map_err(|err| format!("Failed to read data for day 11: {}", err))?
@@ -195,78 +569,206 @@ fn parse(filename: &Path) -> Result<Grid, String> {
     Ok(grid)
 }
 
-pub fn part01(filename: &Path) -> Result<String, String> {
+// `preset=part1|part2`, `neighbourhood=adjacent|sight|radius:R`, `crowding=N`, `empty=N` and `limit=N`
+fn parse_rules(args: &[String], command: &str) -> Result<(Rules, usize), String> {
+    let mut rules = Rules::part1();
+    let mut limit = 10_000;
+
+    for arg in args {
+        let (key, value) = match arg.find('=') {
+            Some(idx) => (&arg[..idx], &arg[idx + 1..]),
+            None => Err(format!(
+                "Usage: {} 11 [preset=part1|part2] [neighbourhood=adjacent|sight|radius:R] [crowding=N] [empty=N] [limit=N]",
+                command
+            ))?,
+        };
+        let number = || usize::from_str(value).map_err(|err| format!("Invalid {} '{}': {}", key, value, err));
+        match key {
+            "preset" if value == "part1" => rules = Rules::part1(),
+            "preset" if value == "part2" => rules = Rules::part2(),
+            "preset" => Err(format!("Unknown preset '{}', expected part1 or part2", value))?,
+            "neighbourhood" => rules.neighbourhood = Neighbourhood::from_str(value)?,
+            "crowding" => rules.crowding = number()?,
+            "empty" => rules.empty = number()?,
+            "limit" => limit = number()?,
+            _ => Err(format!("Unknown option '{}' for {}", key, command))?,
+        }
+    }
+
+    Ok((rules, limit))
+}
+
+pub fn seats(filename: &Path, args: &[String]) -> Result<String, String> {
+    let (rules, limit) = parse_rules(args, "seats")?;
     let mut grid = parse(filename)?;
 
-    grid.simulate(
-        |grid, at, cell| match cell {
-            Cell::Floor => Cell::Floor,
-            Cell::Empty => {
-                if grid.adjacent_next(&at).iter().any(|&cell| cell == Some(&Cell::Taken)) {
-                    Cell::Empty
-                } else {
-                    Cell::Taken
-                }
-            }
-            Cell::Taken => {
-                if grid
-                    .adjacent_next(&at)
-                    .iter()
-                    .filter(|&&cell| cell == Some(&Cell::Taken))
-                    .count()
-                    >= 4
-                {
-                    Cell::Empty
-                } else {
-                    Cell::Taken
-                }
-            }
+    let mut lines = vec![format!("Rules: {}", rules)];
+    let outcome = grid.simulate(
+        |grid, at, cell| rules.apply(grid, at, cell),
+        limit,
+        |round, grid| {
+            lines.push(format!("round {:>4}: {} taken", round, grid.cell_count(&Cell::Taken)));
+            true
         },
-        |grid, prev| grid == prev,
     );
+    lines.push(format!("Outcome: {}", outcome));
+    lines.push(format!("Seats taken: {}", grid.cell_count(&Cell::Taken)));
 
-    Ok(format!(
-        "Seats taken when stabilized: {}",
-        grid.cell_count(&Cell::Taken)
-    ))
+    Ok(lines.join("\n"))
 }
 
-pub fn part02(filename: &Path) -> Result<String, String> {
+pub fn animate(filename: &Path, args: &[String]) -> Result<String, String> {
+    let (output, args) = match args.split_first() {
+        Some((output, args)) if !output.contains('=') => (Path::new(output), args),
+        _ => Err(
+            "Usage: animate 11 OUTPUT.gif|DIRECTORY [scale=N] [palette=FLOOR,EMPTY,TAKEN] [delay=MS] [RULE...]"
+                .to_string(),
+        )?,
+    };
+    let palette = Palette::new(&[[0x20, 0x20, 0x20], [0x40, 0xa0, 0x40], [0xd0, 0x40, 0x40]]);
+    let (options, rest) = render::parse_options(args, palette)?;
+    let (rules, limit) = parse_rules(&rest, "animate")?;
     let mut grid = parse(filename)?;
 
-    grid.simulate(
-        |grid, at, cell| match cell {
-            Cell::Floor => Cell::Floor,
-            Cell::Empty => {
-                if grid
-                    .adjacent_nearest(&at, |&cell| cell != Cell::Floor)
-                    .iter()
-                    .any(|&cell| cell == Some(&Cell::Taken))
-                {
-                    Cell::Empty
-                } else {
-                    Cell::Taken
-                }
-            }
-            Cell::Taken => {
-                if grid
-                    .adjacent_nearest(&at, |&cell| cell != Cell::Floor)
-                    .iter()
-                    .filter(|&&cell| cell == Some(&Cell::Taken))
-                    .count()
-                    >= 5
-                {
-                    Cell::Empty
-                } else {
-                    Cell::Taken
-                }
-            }
+    let scale = options.scale;
+    let mut recorder = Recorder::new(output, options.palette, options.delay);
+    let outcome = grid.simulate(
+        |grid, at, cell| rules.apply(grid, at, cell),
+        limit,
+        |_, grid| {
+            recorder.push(grid.frame(scale));
+            true
+        },
+    );
+
+    Ok(format!("{}\nOutcome: {}", recorder.finish()?, outcome))
+}
+
+pub fn watch(filename: &Path, args: &[String]) -> Result<String, String> {
+    let (delay, rest) = terminal::parse_delay(args)?;
+    let (rules, limit) = parse_rules(&rest, "watch")?;
+    let mut grid = parse(filename)?;
+
+    let mut view = LiveView::new(delay)?;
+    let mut failure = None;
+    let outcome = grid.simulate(
+        |grid, at, cell| rules.apply(grid, at, cell),
+        limit,
+        |round, grid| {
+            let title = format!("Round {}: {} seats taken", round, grid.cell_count(&Cell::Taken));
+            view.show(&title, &grid.styled()).unwrap_or_else(|err| {
+                failure = Some(err);
+                false
+            })
         },
-        |grid, prev| grid == prev,
     );
+    drop(view);
+
+    match failure {
+        Some(err) => Err(err),
+        None => Ok(format!(
+            "Outcome: {}\nSeats taken: {}",
+            outcome,
+            grid.cell_count(&Cell::Taken)
+        )),
+    }
+}
+
+fn time<T>(runs: usize, mut f: impl FnMut() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let mut result = f();
+    for _ in 1..runs {
+        result = f();
+    }
+    (result, start.elapsed() / runs as u32)
+}
+
+pub fn bench(filename: &Path, args: &[String]) -> Result<String, String> {
+    let mut threads = thread::available_parallelism().map_or(1, |threads| threads.get());
+    let mut runs = 5;
+    let mut rule_args = Vec::new();
+    for arg in args {
+        if let Some(value) = arg.strip_prefix("threads=") {
+            threads = usize::from_str(value).map_err(|err| format!("Invalid threads '{}': {}", value, err))?;
+        } else if let Some(value) = arg.strip_prefix("runs=") {
+            runs = usize::from_str(value)
+                .map_err(|err| format!("Invalid runs '{}': {}", value, err))?
+                .max(1);
+        } else {
+            rule_args.push(arg.clone());
+        }
+    }
+    let (rules, limit) = parse_rules(&rule_args, "bench")?;
+    let grid = parse(filename)?;
+
+    let (scanned, scan_time) = time(runs, || {
+        let mut grid = grid.clone();
+        let outcome = grid.simulate(|grid, at, cell| rules.apply(grid, at, cell), limit, |_, _| true);
+        (outcome, grid)
+    });
+    let (graph, build_time) = time(runs, || SeatGraph::new(&grid, rules.neighbourhood));
+    let run_graph = |threads| {
+        time(runs, || {
+            let mut grid = grid.clone();
+            (graph.simulate(&mut grid, rules, threads, limit), grid)
+        })
+    };
+    let mut counts = vec![1];
+    if threads > 1 {
+        counts.push(threads);
+    }
+    let mut graph_times = Vec::new();
+    for &threads in counts.iter() {
+        let (result, time) = run_graph(threads);
+        if result != scanned {
+            Err("The seat graph disagrees with the grid simulation".to_string())?
+        }
+        graph_times.push((threads, time));
+    }
+
+    let speedup = |time: Duration| scan_time.as_secs_f64() / time.as_secs_f64();
+    let graph_line = |threads: usize, time: Duration| {
+        format!(
+            "{:<24}{:>10.3?} ({:.1}x, {:.1}x with build)",
+            format!("seat graph, {} thread{}:", threads, if threads == 1 { "" } else { "s" }),
+            time,
+            speedup(time),
+            speedup(time + build_time)
+        )
+    };
+    let mut lines = vec![
+        format!("Rules: {}", rules),
+        format!(
+            "Seats: {}, neighbour links: {}, outcome: {}, seats taken: {}",
+            graph.cells.len(),
+            graph.neighbours.len(),
+            scanned.0,
+            scanned.1.cell_count(&Cell::Taken)
+        ),
+        format!("{:<24}{:>10.3?} (mean of {} runs)", "grid scan:", scan_time, runs),
+        format!("{:<24}{:>10.3?}", "seat graph build:", build_time),
+    ];
+    lines.extend(graph_times.into_iter().map(|(threads, time)| graph_line(threads, time)));
+    Ok(lines.join("\n"))
+}
 
-    Ok(format!(
-        "Seats taken when stabilized: {}",
-        grid.cell_count(&Cell::Taken)
-    ))
+fn stabilize(filename: &Path, rules: Rules) -> Result<String, String> {
+    let mut grid = parse(filename)?;
+    let graph = SeatGraph::new(&grid, rules.neighbourhood);
+
+    match graph.simulate(&mut grid, rules, 1, usize::MAX) {
+        Outcome::Stable { .. } => Ok(format!(
+            "Seats taken when stabilized: {}",
+            grid.cell_count(&Cell::Taken)
+        )),
+        outcome => Err(format!("Seats never stabilize: {}", outcome)),
+    }
+}
+
+pub fn part01(filename: &Path) -> Result<String, String> {
+    stabilize(filename, Rules::part1())
+}
+
+pub fn part02(filename: &Path) -> Result<String, String> {
+    stabilize(filename, Rules::part2())
 }
@@ -1,156 +1,399 @@
 use std::convert::TryFrom;
+use std::fmt;
 use std::fs;
-use std::path::Path;
+use std::ops::{Add, Mul, Sub};
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use num_traits::{One, Signed, ToPrimitive, Zero};
+
+// action amounts are exact, e.g. `F10`, `F2.5` or `R45/2`
+type Value = BigRational;
+
+#[derive(Clone, Debug, PartialEq)]
 enum Action {
-    North(i32),
-    South(i32),
-    West(i32),
-    East(i32),
+    North(Value),
+    South(Value),
+    West(Value),
+    East(Value),
     // -----
-    Right(i32),
-    Left(i32),
+    Right(Value),
+    Left(Value),
     // -----
-    Forward(i32),
+    Forward(Value),
+}
+
+fn parse_value(text: &str) -> Result<Value, String> {
+    let invalid = |err: &dyn fmt::Display| format!("Failed to parse number '{}': {}", text, err);
+
+    if text.contains('/') {
+        Value::from_str(text).map_err(|err| invalid(&err))
+    } else if let Some(dot) = text.find('.') {
+        let digits = format!("{}{}", &text[..dot], &text[dot + 1..]);
+        let numer = BigInt::from_str(&digits).map_err(|err| invalid(&err))?;
+        let denom = num_traits::pow(BigInt::from(10), text.len() - dot - 1);
+        Ok(Value::new(numer, denom))
+    } else {
+        Ok(Value::from_integer(
+            BigInt::from_str(text).map_err(|err| invalid(&err))?,
+        ))
+    }
 }
 
 impl TryFrom<&str> for Action {
     type Error = String;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        match value.chars().nth(0).unwrap() {
-            'N' => Ok(Self::North(
-                i32::from_str(&value[1..]).map_err(|err| format!("Failed to parse number: {}", err))?,
-            )),
-            'S' => Ok(Self::South(
-                i32::from_str(&value[1..]).map_err(|err| format!("Failed to parse number: {}", err))?,
-            )),
-            'W' => Ok(Self::West(
-                i32::from_str(&value[1..]).map_err(|err| format!("Failed to parse number: {}", err))?,
-            )),
-            'E' => Ok(Self::East(
-                i32::from_str(&value[1..]).map_err(|err| format!("Failed to parse number: {}", err))?,
-            )),
-            'R' => Ok(Self::Right(
-                i32::from_str(&value[1..]).map_err(|err| format!("Failed to parse number: {}", err))?,
-            )),
-            'L' => Ok(Self::Left(
-                i32::from_str(&value[1..]).map_err(|err| format!("Failed to parse number: {}", err))?,
-            )),
-            'F' => Ok(Self::Forward(
-                i32::from_str(&value[1..]).map_err(|err| format!("Failed to parse number: {}", err))?,
-            )),
+        let mut chars = value.chars();
+        let kind = chars.next().ok_or("Empty action")?;
+        let amount = parse_value(chars.as_str())?;
+
+        match kind {
+            'N' => Ok(Self::North(amount)),
+            'S' => Ok(Self::South(amount)),
+            'W' => Ok(Self::West(amount)),
+            'E' => Ok(Self::East(amount)),
+            'R' => Ok(Self::Right(amount)),
+            'L' => Ok(Self::Left(amount)),
+            'F' => Ok(Self::Forward(amount)),
             c => Err(format!("Unknown character: {}", c)),
         }
     }
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
-struct Point {
-    pub east: i32,
-    pub north: i32,
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Action::North(value) => write!(f, "N{}", value),
+            Action::South(value) => write!(f, "S{}", value),
+            Action::West(value) => write!(f, "W{}", value),
+            Action::East(value) => write!(f, "E{}", value),
+            Action::Right(value) => write!(f, "R{}", value),
+            Action::Left(value) => write!(f, "L{}", value),
+            Action::Forward(value) => write!(f, "F{}", value),
+        }
+    }
 }
 
-impl From<(i32, i32)> for Point {
-    fn from(p: (i32, i32)) -> Self {
-        Self { east: p.0, north: p.1 }
+// numbers a ship can navigate with, exact rationals or floating point
+trait Scalar: Clone + PartialOrd + fmt::Display + Signed + ToPrimitive {
+    fn from_value(value: &Value) -> Self;
+
+    // `point` turned counterclockwise by `degrees`
+    fn rotate(point: &Point<Self>, degrees: &Value) -> Result<Point<Self>, String>;
+}
+
+// `degrees` as a number of counterclockwise quarter turns in 0..4, if it is a multiple of 90
+fn quarter_turns(degrees: &Value) -> Option<u8> {
+    let turns = degrees / Value::from_integer(BigInt::from(90));
+    if turns.is_integer() {
+        let four = BigInt::from(4);
+        ((turns.to_integer() % &four + &four) % &four).to_u8()
+    } else {
+        None
     }
 }
 
-#[derive(Copy, Clone, Debug)]
-struct Ship {
-    at: Point,
-    waypoint: Point,
-    heading: i32,
+fn rotate_quarters<T: Scalar>(point: &Point<T>, turns: u8) -> Point<T> {
+    let (east, north) = (point.east.clone(), point.north.clone());
+    match turns {
+        0 => Point { east, north },
+        1 => Point {
+            east: -north,
+            north: east,
+        },
+        2 => Point {
+            east: -east,
+            north: -north,
+        },
+        _ => Point {
+            east: north,
+            north: -east,
+        },
+    }
 }
 
-impl Ship {
-    fn new(waypoint: Point, heading: i32) -> Self {
-        Ship {
-            at: (0, 0).into(),
-            waypoint,
-            heading,
-        }
-    }
-
-    fn update_pt1(mut self, action: &Action) -> Self {
-        match action {
-            Action::North(dist) => self.at.north += dist,
-            Action::South(dist) => self.at.north -= dist,
-            Action::West(dist) => self.at.east -= dist,
-            Action::East(dist) => self.at.east += dist,
-            // -----
-            Action::Right(deg) => self.heading = new_heading(self.heading, -*deg),
-            Action::Left(deg) => self.heading = new_heading(self.heading, *deg),
-            // -----
-            Action::Forward(dist) => match &self.heading {
-                0 => self.at.north += dist,
-                90 => self.at.east -= dist,
-                180 => self.at.north -= dist,
-                270 => self.at.east += dist,
-                _ => panic!("Non-90-degree increment heading"),
-            },
+impl Scalar for Value {
+    fn from_value(value: &Value) -> Self {
+        value.clone()
+    }
+
+    // only quarter turns keep rational coordinates
+    fn rotate(point: &Point<Self>, degrees: &Value) -> Result<Point<Self>, String> {
+        match quarter_turns(degrees) {
+            Some(turns) => Ok(rotate_quarters(point, turns)),
+            None => Err(format!(
+                "Turning {} degrees is not exact, exact navigation only turns in multiples of 90 degrees",
+                degrees.abs()
+            )),
         }
+    }
+}
 
-        self
+impl Scalar for f64 {
+    fn from_value(value: &Value) -> Self {
+        value.to_f64().unwrap_or(f64::NAN)
     }
 
-    fn update_pt2(mut self, action: &Action) -> Self {
-        match action {
-            Action::North(dist) => self.waypoint.north += dist,
-            Action::South(dist) => self.waypoint.north -= dist,
-            Action::West(dist) => self.waypoint.east -= dist,
-            Action::East(dist) => self.waypoint.east += dist,
-            // -----
-            Action::Right(deg) => {
-                self.waypoint = rotate_waypoint(self.waypoint, -*deg);
-                self.heading = new_heading(self.heading, -*deg)
-            }
-            Action::Left(deg) => {
-                self.waypoint = rotate_waypoint(self.waypoint, *deg);
-                self.heading = new_heading(self.heading, *deg);
-            }
-            // -----
-            Action::Forward(times) => {
-                self.at.north += times * self.waypoint.north;
-                self.at.east += times * self.waypoint.east;
-            }
+    // quarter turns are done exactly so the usual routes do not pick up rounding errors
+    fn rotate(point: &Point<Self>, degrees: &Value) -> Result<Point<Self>, String> {
+        if let Some(turns) = quarter_turns(degrees) {
+            return Ok(rotate_quarters(point, turns));
+        }
+
+        let (sin, cos) = f64::from_value(degrees).to_radians().sin_cos();
+        Ok(Point {
+            east: point.east * cos - point.north * sin,
+            north: point.east * sin + point.north * cos,
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+struct Point<T> {
+    pub east: T,
+    pub north: T,
+}
+
+impl<T: Scalar> Point<T> {
+    fn new(east: i32, north: i32) -> Self {
+        Point {
+            east: T::from_value(&Value::from_integer(BigInt::from(east))),
+            north: T::from_value(&Value::from_integer(BigInt::from(north))),
         }
+    }
 
-        self
+    fn manhattan(&self) -> T {
+        self.east.abs() + self.north.abs()
     }
 
-    fn manhattan(&self) -> i32 {
-        i32::abs(self.at.east) + i32::abs(self.at.north)
+    fn length(&self) -> f64 {
+        let (east, north) = (self.east.to_f64().unwrap(), self.north.to_f64().unwrap());
+        east.hypot(north)
     }
 }
 
-fn new_heading(old: i32, update: i32) -> i32 {
-    let mut new = old + update;
-    loop {
-        if new < 0 {
-            new += 360;
-            continue;
-        } else if new >= 360 {
-            new -= 360;
-            continue;
+impl<T: Scalar> Add for &Point<T> {
+    type Output = Point<T>;
+
+    fn add(self, other: Self) -> Point<T> {
+        Point {
+            east: self.east.clone() + other.east.clone(),
+            north: self.north.clone() + other.north.clone(),
+        }
+    }
+}
+
+impl<T: Scalar> Sub for &Point<T> {
+    type Output = Point<T>;
+
+    fn sub(self, other: Self) -> Point<T> {
+        Point {
+            east: self.east.clone() - other.east.clone(),
+            north: self.north.clone() - other.north.clone(),
+        }
+    }
+}
+
+impl<T: Scalar> Mul<&T> for &Point<T> {
+    type Output = Point<T>;
+
+    fn mul(self, factor: &T) -> Point<T> {
+        Point {
+            east: self.east.clone() * factor.clone(),
+            north: self.north.clone() * factor.clone(),
         }
+    }
+}
 
-        break;
+impl<T: Scalar> fmt::Display for Point<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.east, self.north)
     }
+}
 
-    new
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Semantics {
+    // N/S/E/W move the ship, F moves it along its heading
+    Heading,
+    // N/S/E/W move the waypoint, F moves the ship towards the waypoint that many times
+    Waypoint,
 }
 
-fn rotate_waypoint(mut waypoint: Point, rot: i32) -> Point {
-    for _ in 0..i32::abs(rot / 90) {
-        let tmp = waypoint.east;
-        waypoint.east = -i32::signum(rot) * waypoint.north;
-        waypoint.north = i32::signum(rot) * tmp;
+impl Semantics {
+    fn vector(&self) -> &'static str {
+        match self {
+            Semantics::Heading => "heading",
+            Semantics::Waypoint => "waypoint",
+        }
     }
+}
+
+impl FromStr for Semantics {
+    type Err = String;
 
-    waypoint
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "part1" => Ok(Semantics::Heading),
+            "part2" => Ok(Semantics::Waypoint),
+            _ => Err(format!("Unknown semantics '{}', expected part1 or part2", s)),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct State<T> {
+    at: Point<T>,
+    // the unit heading vector for part 1, the waypoint relative to the ship for part 2
+    vector: Point<T>,
+}
+
+#[derive(Clone, Debug)]
+struct Ship<T> {
+    semantics: Semantics,
+    // every state from the start, one more than the number of actions taken
+    trajectory: Vec<State<T>>,
+}
+
+impl<T: Scalar> Ship<T> {
+    // facing east, with the waypoint 10 east and 1 north for part 2
+    fn new(semantics: Semantics) -> Self {
+        let vector = match semantics {
+            Semantics::Heading => Point::new(1, 0),
+            Semantics::Waypoint => Point::new(10, 1),
+        };
+
+        Ship {
+            semantics,
+            trajectory: vec![State {
+                at: Point::new(0, 0),
+                vector,
+            }],
+        }
+    }
+
+    fn state(&self) -> &State<T> {
+        self.trajectory.last().unwrap()
+    }
+
+    fn update(mut self, action: &Action) -> Result<Self, String> {
+        let State { mut at, mut vector } = self.state().clone();
+        let amount = |value: &Value| T::from_value(value);
+        let offset = match action {
+            Action::North(value) => Some(Point {
+                east: T::zero(),
+                north: amount(value),
+            }),
+            Action::South(value) => Some(Point {
+                east: T::zero(),
+                north: -amount(value),
+            }),
+            Action::East(value) => Some(Point {
+                east: amount(value),
+                north: T::zero(),
+            }),
+            Action::West(value) => Some(Point {
+                east: -amount(value),
+                north: T::zero(),
+            }),
+            _ => None,
+        };
+
+        match (action, offset) {
+            (_, Some(offset)) => match self.semantics {
+                Semantics::Heading => at = &at + &offset,
+                Semantics::Waypoint => vector = &vector + &offset,
+            },
+            (Action::Left(degrees), _) => vector = T::rotate(&vector, degrees)?,
+            (Action::Right(degrees), _) => vector = T::rotate(&vector, &-degrees)?,
+            (Action::Forward(value), _) => at = &at + &(&vector * &amount(value)),
+            _ => unreachable!(),
+        }
+
+        self.trajectory.push(State { at, vector });
+        Ok(self)
+    }
+
+    fn manhattan(&self) -> T {
+        self.state().at.manhattan()
+    }
+
+    // the length of the path sailed, in straight segments between states
+    fn travelled(&self) -> f64 {
+        self.trajectory
+            .windows(2)
+            .map(|pair| (&pair[1].at - &pair[0].at).length())
+            .sum()
+    }
+
+    // south-west and north-east corners of every position sailed through
+    fn bounding_box(&self) -> (Point<T>, Point<T>) {
+        let start = self.trajectory[0].at.clone();
+        self.trajectory
+            .iter()
+            .fold((start.clone(), start), |(low, high), state| {
+                let min = |a: T, b: &T| if *b < a { b.clone() } else { a };
+                let max = |a: T, b: &T| if *b > a { b.clone() } else { a };
+                (
+                    Point {
+                        east: min(low.east, &state.at.east),
+                        north: min(low.north, &state.at.north),
+                    },
+                    Point {
+                        east: max(high.east, &state.at.east),
+                        north: max(high.north, &state.at.north),
+                    },
+                )
+            })
+    }
+
+    // the first position with the largest manhattan distance from the start, and the step reaching it
+    fn furthest(&self) -> (usize, &Point<T>) {
+        let start = &self.trajectory[0].at;
+        let mut furthest = (0, start);
+        for (idx, state) in self.trajectory.iter().enumerate() {
+            if (&state.at - start).manhattan() > (furthest.1 - start).manhattan() {
+                furthest = (idx, &state.at);
+            }
+        }
+        furthest
+    }
+}
+
+fn sail<T: Scalar>(actions: &[Action], semantics: Semantics) -> Result<Ship<T>, String> {
+    actions
+        .iter()
+        .enumerate()
+        .try_fold(Ship::new(semantics), |ship, (idx, action)| {
+            ship.update(action)
+                .map_err(|err| format!("Action {} ({}): {}", idx + 1, action, err))
+        })
+}
+
+fn describe<T: Scalar>(ship: &Ship<T>) -> String {
+    let state = ship.state();
+    let (low, high) = ship.bounding_box();
+    let (step, furthest) = ship.furthest();
+
+    [
+        format!("Position: {}", state.at),
+        match ship.semantics {
+            Semantics::Heading => format!("Heading: {}", state.vector),
+            Semantics::Waypoint => format!("Waypoint: {}", state.vector),
+        },
+        format!("Manhattan distance: {}", ship.manhattan()),
+        format!("Distance travelled: {:.3}", ship.travelled()),
+        format!("Bounding box: {} to {}", low, high),
+        format!(
+            "Furthest point: {} after action {}, manhattan distance {}",
+            furthest,
+            step,
+            furthest.manhattan()
+        ),
+    ]
+    .join("\n")
 }
 
 fn parse(filename: &Path) -> Result<Vec<Action>, String> {
@@ -161,20 +404,427 @@ fn parse(filename: &Path) -> Result<Vec<Action>, String> {
         .collect()
 }
 
-pub fn part01(filename: &Path) -> Result<String, String> {
-    let data = parse(filename)?;
+// south-west corner and size of the drawing with some room around it, the ship is drawn with north up
+fn view_box<T: Scalar>(ship: &Ship<T>) -> (f64, f64, f64, f64) {
+    let mut points: Vec<Point<T>> = ship.trajectory.iter().map(|state| state.at.clone()).collect();
+    if ship.semantics == Semantics::Waypoint {
+        points.extend(ship.trajectory.iter().map(|state| &state.at + &state.vector));
+    }
+    let coords = |point: &Point<T>| (point.east.to_f64().unwrap(), point.north.to_f64().unwrap());
+
+    let (mut west, mut south) = coords(&points[0]);
+    let (mut east, mut north) = (west, south);
+    for (x, y) in points.iter().map(coords) {
+        west = west.min(x);
+        east = east.max(x);
+        south = south.min(y);
+        north = north.max(y);
+    }
+    let margin = (east - west).max(north - south).max(1.0) * 0.05;
 
-    let ship = Ship::new(Point::default(), 270);
-    let ship = data.iter().fold(ship, |ship, action| ship.update_pt1(&action));
+    (
+        west - margin,
+        south - margin,
+        east - west + 2.0 * margin,
+        north - south + 2.0 * margin,
+    )
+}
+
+// a standalone SVG of the route, with the waypoint at every step for part 2 and the manhattan distance
+// sailed as a dashed east-then-north path from the start
+fn svg<T: Scalar>(ship: &Ship<T>) -> String {
+    let (west, south, width, height) = view_box(ship);
+    let size = width.max(height);
+    // svg y grows downwards
+    let coords = |point: &Point<T>| (point.east.to_f64().unwrap(), 0.0 - point.north.to_f64().unwrap());
+    let xy = |point: &Point<T>| {
+        let (x, y) = coords(point);
+        format!("{:.3},{:.3}", x, y)
+    };
+    let start = &ship.trajectory[0].at;
+    let end = &ship.state().at;
+    let corner = Point {
+        east: end.east.clone(),
+        north: start.north.clone(),
+    };
+
+    let mut lines = vec![
+        format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{:.3} {:.3} {:.3} {:.3}" width="800" height="{:.0}">"#,
+            west,
+            -south - height,
+            width,
+            height,
+            800.0 * height / width
+        ),
+        format!(
+            r#"<rect x="{:.3}" y="{:.3}" width="{:.3}" height="{:.3}" fill="white"/>"#,
+            west,
+            -south - height,
+            width,
+            height
+        ),
+    ];
+
+    if ship.semantics == Semantics::Waypoint {
+        lines.push(format!(
+            r#"<g stroke="steelblue" stroke-opacity="0.4" stroke-width="{:.3}">"#,
+            size / 1000.0
+        ));
+        for state in ship.trajectory.iter() {
+            let (x1, y1) = coords(&state.at);
+            let (x2, y2) = coords(&(&state.at + &state.vector));
+            lines.push(format!(
+                r#"<line x1="{:.3}" y1="{:.3}" x2="{:.3}" y2="{:.3}"/>"#,
+                x1, y1, x2, y2
+            ));
+        }
+        lines.push("</g>".to_string());
+    }
+
+    lines.push(format!(
+        r#"<polyline points="{} {} {}" fill="none" stroke="grey" stroke-width="{:.3}" stroke-dasharray="{:.3}"/>"#,
+        xy(start),
+        xy(&corner),
+        xy(end),
+        size / 500.0,
+        size / 100.0
+    ));
+    lines.push(format!(
+        r#"<polyline points="{}" fill="none" stroke="black" stroke-width="{:.3}" stroke-linejoin="round"/>"#,
+        ship.trajectory
+            .iter()
+            .map(|state| xy(&state.at))
+            .collect::<Vec<String>>()
+            .join(" "),
+        size / 400.0
+    ));
+    for (point, colour) in [(start, "green"), (end, "red")] {
+        let (x, y) = coords(point);
+        lines.push(format!(
+            r#"<circle cx="{:.3}" cy="{:.3}" r="{:.3}" fill="{}"/>"#,
+            x,
+            y,
+            size / 100.0,
+            colour
+        ));
+    }
+    lines.push(format!(
+        r#"<text x="{:.3}" y="{:.3}" font-family="sans-serif" font-size="{:.3}">Manhattan distance: {}</text>"#,
+        west + size / 50.0,
+        -south - height + size / 25.0,
+        size / 40.0,
+        ship.manhattan()
+    ));
+    lines.push("</svg>".to_string());
+
+    lines.join("\n") + "\n"
+}
+
+// the shortest actions moving the ship by `offset`, one per axis
+fn translation(offset: &Point<Value>) -> Vec<Action> {
+    let mut actions = Vec::new();
+    if offset.east.is_positive() {
+        actions.push(Action::East(offset.east.clone()));
+    } else if offset.east.is_negative() {
+        actions.push(Action::West(-offset.east.clone()));
+    }
+    if offset.north.is_positive() {
+        actions.push(Action::North(offset.north.clone()));
+    } else if offset.north.is_negative() {
+        actions.push(Action::South(-offset.north.clone()));
+    }
+    actions
+}
+
+fn turn(quarters: u8) -> Action {
+    let degrees = |value: i32| Value::from_integer(BigInt::from(value));
+    match quarters {
+        1 => Action::Left(degrees(90)),
+        2 => Action::Left(degrees(180)),
+        _ => Action::Right(degrees(90)),
+    }
+}
+
+// the shortest actions turning or moving the waypoint from `from` to `to`, at most two
+fn retarget(from: &Point<Value>, to: &Point<Value>) -> Vec<Action> {
+    if from == to {
+        return Vec::new();
+    }
+    match (1..4).find(|&quarters| &rotate_quarters(from, quarters) == to) {
+        Some(quarters) => vec![turn(quarters)],
+        None => translation(&(to - from)),
+    }
+}
+
+// `target` as `a * u + b * v`, if it takes both
+fn decompose(target: &Point<Value>, u: &Point<Value>, v: &Point<Value>) -> Option<(Value, Value)> {
+    let det = &u.east * &v.north - &u.north * &v.east;
+    if det.is_zero() {
+        return None;
+    }
+    let a = (&target.east * &v.north - &target.north * &v.east) / &det;
+    let b = (&u.east * &target.north - &u.north * &target.east) / &det;
+    if !a.is_zero() && !b.is_zero() {
+        Some((a, b))
+    } else {
+        None
+    }
+}
+
+// the sailing with one forward move of `amount` each through the `stops` waypoints
+fn through(start: &Point<Value>, stops: &[(Point<Value>, Value)], end: &Point<Value>) -> Vec<Action> {
+    let mut actions = Vec::new();
+    let mut waypoint = start;
+    for (stop, amount) in stops {
+        actions.extend(retarget(waypoint, stop));
+        actions.push(Action::Forward(amount.clone()));
+        waypoint = stop;
+    }
+    actions.extend(retarget(waypoint, end));
+    actions
+}
+
+// With a waypoint every action either moves the ship or changes the waypoint, and changing it never takes
+// more than two actions. A single forward move therefore reaches any position in at most five actions, and
+// three forward moves already need at least five. The shortest route is found among those with at most two
+// forward moves, trying every waypoint where either the forward moves or the waypoint changes get shorter.
+// Forward moves may go backwards, as negative amounts are valid actions too.
+fn shortest_with_waypoint(start: &Point<Value>, end: &State<Value>) -> Vec<Action> {
+    let (target, waypoint) = (&end.at, &end.vector);
+    if target == &Point::new(0, 0) {
+        return retarget(start, waypoint);
+    }
+    let mut routes = Vec::new();
+
+    // one forward move of `amount` along `target / amount`, with the amounts where that waypoint lines up
+    // with the start or end waypoint
+    let mut amounts = vec![Value::one()];
+    for anchor in [start, waypoint] {
+        for quarters in 0..4 {
+            let turned = rotate_quarters(anchor, quarters);
+            if !turned.east.is_zero() {
+                amounts.push(&target.east / &turned.east);
+            }
+            if !turned.north.is_zero() {
+                amounts.push(&target.north / &turned.north);
+            }
+        }
+    }
+    for amount in amounts.into_iter().filter(|amount| !amount.is_zero()) {
+        let stop = target * &amount.recip();
+        routes.push(through(start, &[(stop, amount)], waypoint));
+    }
+
+    // two forward moves, the first along the start waypoint or the second along the end waypoint, with the
+    // other waypoint one change away from both
+    let mut stops = vec![start.clone(), waypoint.clone()];
+    for quarters in 1..4 {
+        stops.push(rotate_quarters(start, quarters));
+        stops.push(rotate_quarters(waypoint, quarters));
+    }
+    stops.push(Point {
+        east: waypoint.east.clone(),
+        north: start.north.clone(),
+    });
+    stops.push(Point {
+        east: start.east.clone(),
+        north: waypoint.north.clone(),
+    });
+    for stop in stops.iter() {
+        for (first, second) in [(start, stop), (stop, waypoint)] {
+            if let Some((a, b)) = decompose(target, first, second) {
+                let stops = [(first.clone(), a), (second.clone(), b)];
+                routes.push(through(start, &stops, waypoint));
+            }
+        }
+    }
+    // when the start and end waypoint share a row or column any waypoint along it is one change away from
+    // both, as long as the two forward moves `a` and `b` add up to the target along it, `a + b = target / shared`
+    let axes: [fn(&Point<Value>) -> &Value; 2] = [|point| &point.north, |point| &point.east];
+    for axis in axes {
+        let shared = axis(start);
+        if shared != axis(waypoint) || (shared.is_zero() && !axis(target).is_zero()) {
+            continue;
+        }
+        let (a, b) = if shared.is_zero() {
+            (Value::one(), Value::one())
+        } else if axis(target).is_zero() {
+            (Value::one(), -Value::one())
+        } else {
+            let half = axis(target) / shared / Value::from_integer(BigInt::from(2));
+            (half.clone(), half)
+        };
+        let stops = [
+            (&(target - &(waypoint * &b)) * &a.recip(), a.clone()),
+            (waypoint.clone(), b.clone()),
+        ];
+        routes.push(through(start, &stops, waypoint));
+        let stops = [(start.clone(), a.clone()), (&(target - &(start * &a)) * &b.recip(), b)];
+        routes.push(through(start, &stops, waypoint));
+    }
+
+    routes.into_iter().min_by_key(Vec::len).unwrap()
+}
+
+// the shortest actions with the same final position and heading or waypoint as `actions`
+fn shortest(actions: &[Action], semantics: Semantics) -> Result<Vec<Action>, String> {
+    let ship = sail::<Value>(actions, semantics)?;
+    let (start, end) = (&ship.trajectory[0], ship.state());
+
+    let simplified = match semantics {
+        // every action changes one coordinate or the heading, so one for each that differs
+        Semantics::Heading => {
+            let mut simplified = translation(&end.at);
+            if let Some(quarters) = (1..4).find(|&quarters| rotate_quarters(&start.vector, quarters) == end.vector) {
+                simplified.push(turn(quarters));
+            }
+            simplified
+        }
+        Semantics::Waypoint => shortest_with_waypoint(&start.vector, end),
+    };
+
+    if let Err(difference) = equivalent(actions, &simplified, semantics)? {
+        Err(format!("The simplified route is not equivalent: {}", difference))?
+    }
+    if simplified.len() > actions.len() {
+        return Ok(actions.to_vec());
+    }
+    Ok(simplified)
+}
+
+// the final state both routes share, or how they differ
+fn equivalent(
+    first: &[Action],
+    second: &[Action],
+    semantics: Semantics,
+) -> Result<Result<State<Value>, String>, String> {
+    let first = sail::<Value>(first, semantics)?.state().clone();
+    let second = sail::<Value>(second, semantics)?.state().clone();
+    let vector = semantics.vector();
+
+    Ok(if first == second {
+        Ok(first)
+    } else {
+        Err(format!(
+            "the first ends at {} with {} {}, the second at {} with {} {}",
+            first.at, vector, first.vector, second.at, vector, second.vector
+        ))
+    })
+}
+
+struct Options {
+    semantics: Semantics,
+    exact: bool,
+    route: PathBuf,
+}
+
+// `part1|part2`, `exact|float` and `route=FILE` in any order
+fn parse_options(filename: &Path, args: &[String], usage: &str) -> Result<Options, String> {
+    let mut options = Options {
+        semantics: Semantics::Heading,
+        exact: true,
+        route: filename.to_path_buf(),
+    };
+    for arg in args {
+        match arg.as_str() {
+            "exact" => options.exact = true,
+            "float" => options.exact = false,
+            _ if arg.starts_with("route=") => options.route = Path::new(&arg["route=".len()..]).to_path_buf(),
+            _ => options.semantics = Semantics::from_str(arg).map_err(|_| usage.to_string())?,
+        }
+    }
+
+    Ok(options)
+}
+
+pub fn navigate(filename: &Path, args: &[String]) -> Result<String, String> {
+    let options = parse_options(
+        filename,
+        args,
+        "Usage: navigate 12 [part1|part2] [exact|float] [route=FILE]",
+    )?;
+    let actions = parse(&options.route)?;
+
+    if options.exact {
+        Ok(describe(&sail::<Value>(&actions, options.semantics)?))
+    } else {
+        Ok(describe(&sail::<f64>(&actions, options.semantics)?))
+    }
+}
+
+pub fn plot(filename: &Path, args: &[String]) -> Result<String, String> {
+    let usage = "Usage: plot 12 OUTPUT.svg [part1|part2] [exact|float] [route=FILE]";
+    let (output, args) = match args.split_first() {
+        Some((output, args)) => (Path::new(output), args),
+        None => Err(usage.to_string())?,
+    };
+    let options = parse_options(filename, args, usage)?;
+    let actions = parse(&options.route)?;
+
+    let drawing = if options.exact {
+        svg(&sail::<Value>(&actions, options.semantics)?)
+    } else {
+        svg(&sail::<f64>(&actions, options.semantics)?)
+    };
+    fs::write(output, drawing).map_err(|err| format!("Failed to write {}: {}", output.display(), err))?;
+
+    Ok(format!(
+        "Wrote the route of {} actions to {}",
+        actions.len(),
+        output.display()
+    ))
+}
+
+pub fn simplify(filename: &Path, args: &[String]) -> Result<String, String> {
+    let options = parse_options(filename, args, "Usage: simplify 12 [part1|part2] [route=FILE]")?;
+    if !options.exact {
+        Err("Routes are only simplified exactly".to_string())?
+    }
+    let actions = parse(&options.route)?;
+    let simplified = shortest(&actions, options.semantics)?;
+
+    // keep stdout to the route so it can be saved and sailed again
+    eprintln!("Simplified {} actions to {}", actions.len(), simplified.len());
+    Ok(simplified
+        .iter()
+        .map(Action::to_string)
+        .collect::<Vec<String>>()
+        .join("\n"))
+}
+
+pub fn compare(filename: &Path, args: &[String]) -> Result<String, String> {
+    let usage = "Usage: compare 12 OTHER [part1|part2] [route=FILE]";
+    let (other, args) = match args.split_first() {
+        Some((other, args)) => (Path::new(other), args),
+        None => Err(usage.to_string())?,
+    };
+    let options = parse_options(filename, args, usage)?;
+    if !options.exact {
+        Err("Routes are only compared exactly".to_string())?
+    }
+
+    Ok(
+        match equivalent(&parse(&options.route)?, &parse(other)?, options.semantics)? {
+            Ok(state) => format!(
+                "Equivalent: both end at {} with {} {}",
+                state.at,
+                options.semantics.vector(),
+                state.vector
+            ),
+            Err(difference) => format!("Not equivalent: {}", difference),
+        },
+    )
+}
+
+pub fn part01(filename: &Path) -> Result<String, String> {
+    let ship = sail::<Value>(&parse(filename)?, Semantics::Heading)?;
 
     Ok(format!("Ship have moved # manhattan distance: {}", ship.manhattan()))
 }
 
 pub fn part02(filename: &Path) -> Result<String, String> {
-    let data = parse(filename)?;
-
-    let ship = Ship::new((10, 1).into(), 270);
-    let ship = data.iter().fold(ship, |ship, action| ship.update_pt2(&action));
+    let ship = sail::<Value>(&parse(filename)?, Semantics::Waypoint)?;
 
     Ok(format!("Ship have moved # manhattan distance: {}", ship.manhattan()))
 }
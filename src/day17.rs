@@ -1,10 +1,17 @@
 use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::Path;
+use std::str::FromStr;
 
+use crossterm::style::Color;
 use itertools::Itertools;
 
-fn parse(filename: &Path) -> Result<HashSet<(i8, i8, i8, i8)>, String> {
+use crate::render::{self, Frame, Palette, Recorder};
+use crate::terminal::{self, LiveView};
+
+type Coord = (i8, i8, i8, i8);
+
+fn parse(filename: &Path) -> Result<HashSet<Coord>, String> {
     Ok(fs::read_to_string(filename)
         .map_err(|err| format!("Failed to read data for day 17: {}", err))?
         .lines()
@@ -19,7 +26,7 @@ fn parse(filename: &Path) -> Result<HashSet<(i8, i8, i8, i8)>, String> {
         .collect())
 }
 
-fn neighboring(origin: (i8, i8, i8, i8), three: bool) -> impl Iterator<Item = ((i8, i8, i8, i8), i8)> {
+fn neighboring(origin: Coord, three: bool) -> impl Iterator<Item = (Coord, i8)> {
     (-1..2)
         .cartesian_product(-1..2)
         .cartesian_product(-1..2)
@@ -32,7 +39,7 @@ fn neighboring(origin: (i8, i8, i8, i8), three: bool) -> impl Iterator<Item = ((
         })
 }
 
-fn step(grid: HashSet<(i8, i8, i8, i8)>, three: bool) -> HashSet<(i8, i8, i8, i8)> {
+fn step(grid: HashSet<Coord>, three: bool) -> HashSet<Coord> {
     let capacity = 81 * grid.len();
     grid.into_iter()
         .map(|coord| neighboring(coord, three))
@@ -55,24 +62,192 @@ fn step(grid: HashSet<(i8, i8, i8, i8)>, three: bool) -> HashSet<(i8, i8, i8, i8
         .collect()
 }
 
-pub fn part01(filename: &Path) -> Result<String, String> {
-    let mut cube = parse(filename)?;
+// runs `steps` cycles, calling `observe` with the cycle number and cube before the first and after every cycle,
+// stopping early when it returns false
+fn simulate<F>(mut cube: HashSet<Coord>, three: bool, steps: usize, mut observe: F) -> HashSet<Coord>
+where
+    F: FnMut(usize, &HashSet<Coord>) -> bool,
+{
+    if !observe(0, &cube) {
+        return cube;
+    }
+    for cycle in 1..=steps {
+        cube = step(cube, three);
+        if !observe(cycle, &cube) {
+            break;
+        }
+    }
+    cube
+}
 
-    let steps = 6;
-    for _ in 0..steps {
-        cube = step(cube, true);
+// inclusive (min, max) of every axis over all the cubes
+fn bounds<'a>(cubes: impl Iterator<Item = &'a HashSet<Coord>>) -> [(i8, i8); 4] {
+    let mut bounds = [(i8::MAX, i8::MIN); 4];
+    for &(x, y, z, w) in cubes.flatten() {
+        for (axis, value) in [x, y, z, w].iter().enumerate() {
+            bounds[axis] = (bounds[axis].0.min(*value), bounds[axis].1.max(*value));
+        }
     }
+    bounds
+}
 
-    Ok(format!("Active cells: {}", cube.len()))
+// one x/y tile per z/w slice, z increasing to the right and w downwards, with gaps (0), inactive (1)
+// and active (2) cells as palette indices
+fn frame(cube: &HashSet<Coord>, bounds: &[(i8, i8); 4], scale: usize) -> Frame {
+    let span = |axis: usize| (bounds[axis].1 as isize - bounds[axis].0 as isize + 1).max(0) as usize;
+    let (tile_width, tile_height) = (span(0), span(1));
+    let (tiles_across, tiles_down) = (span(2), span(3));
+
+    let mut frame = Frame::new(
+        (tiles_across * (tile_width + 1)).saturating_sub(1) * scale,
+        (tiles_down * (tile_height + 1)).saturating_sub(1) * scale,
+    );
+    for tile_y in 0..tiles_down {
+        for tile_x in 0..tiles_across {
+            for y in 0..tile_height {
+                for x in 0..tile_width {
+                    let coord = (
+                        bounds[0].0 + x as i8,
+                        bounds[1].0 + y as i8,
+                        bounds[2].0 + tile_x as i8,
+                        bounds[3].0 + tile_y as i8,
+                    );
+                    frame.cell(
+                        tile_x * (tile_width + 1) + x,
+                        tile_y * (tile_height + 1) + y,
+                        scale,
+                        if cube.contains(&coord) { 2 } else { 1 },
+                    );
+                }
+            }
+        }
+    }
+    frame
 }
 
-pub fn part02(filename: &Path) -> Result<String, String> {
-    let mut cube = parse(filename)?;
+// `dims=3|4` and `steps=N`
+fn parse_run(args: &[String], command: &str) -> Result<(bool, usize), String> {
+    let mut three = true;
+    let mut steps = 6;
+
+    for arg in args {
+        if let Some(value) = arg.strip_prefix("dims=") {
+            three = match value {
+                "3" => true,
+                "4" => false,
+                _ => Err(format!("Invalid dims '{}', expected 3 or 4", value))?,
+            };
+        } else if let Some(value) = arg.strip_prefix("steps=") {
+            steps = match usize::from_str(value) {
+                // coordinates are stored as i8 and grow by one every step
+                Ok(steps) if steps <= 100 => steps,
+                Ok(_) => Err("At most 100 steps are supported".to_string())?,
+                Err(err) => Err(format!("Invalid steps '{}': {}", value, err))?,
+            };
+        } else {
+            Err(format!("Unknown option '{}' for {}", arg, command))?
+        }
+    }
+
+    Ok((three, steps))
+}
+
+pub fn animate(filename: &Path, args: &[String]) -> Result<String, String> {
+    let (output, args) = match args.split_first() {
+        Some((output, args)) if !output.contains('=') => (Path::new(output), args),
+        _ => Err(
+            "Usage: animate 17 OUTPUT.gif|DIRECTORY [scale=N] [palette=GAP,INACTIVE,ACTIVE] [delay=MS] [dims=3|4] [steps=N]"
+                .to_string(),
+        )?,
+    };
+    let palette = Palette::new(&[[0x10, 0x10, 0x18], [0x30, 0x30, 0x40], [0xf0, 0xd0, 0x40]]);
+    let (options, rest) = render::parse_options(args, palette)?;
+    let (three, steps) = parse_run(&rest, "animate")?;
+
+    // every slice is laid out for the final extent so the tiles stay put between frames
+    let mut cubes = Vec::with_capacity(steps + 1);
+    simulate(parse(filename)?, three, steps, |_, cube| {
+        cubes.push(cube.clone());
+        true
+    });
+    let bounds = bounds(cubes.iter());
+
+    let mut recorder = Recorder::new(output, options.palette, options.delay);
+    for cube in cubes.iter() {
+        recorder.push(frame(cube, &bounds, options.scale));
+    }
 
-    let steps = 6;
-    for _ in 0..steps {
-        cube = step(cube, false);
+    Ok(format!(
+        "{}\nActive cells: {}",
+        recorder.finish()?,
+        cubes.last().unwrap().len()
+    ))
+}
+
+// the x/y plane of the cube at `z` and `w`, spanning every active cell on any slice
+fn styled_slice(cube: &HashSet<Coord>, z: i8, w: i8) -> Vec<Vec<(char, Color)>> {
+    let bounds = bounds(std::iter::once(cube));
+    (bounds[1].0..=bounds[1].1)
+        .map(|y| {
+            (bounds[0].0..=bounds[0].1)
+                .map(|x| {
+                    if cube.contains(&(x, y, z, w)) {
+                        ('#', Color::Yellow)
+                    } else {
+                        ('.', Color::DarkGrey)
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+pub fn watch(filename: &Path, args: &[String]) -> Result<String, String> {
+    let (delay, rest) = terminal::parse_delay(args)?;
+    let (mut z, mut w) = (0, 0);
+    let mut run_args = Vec::new();
+    for arg in rest {
+        if let Some(value) = arg.strip_prefix("z=") {
+            z = i8::from_str(value).map_err(|err| format!("Invalid z '{}': {}", value, err))?;
+        } else if let Some(value) = arg.strip_prefix("w=") {
+            w = i8::from_str(value).map_err(|err| format!("Invalid w '{}': {}", value, err))?;
+        } else {
+            run_args.push(arg);
+        }
+    }
+    let (three, steps) = parse_run(&run_args, "watch")?;
+    if three && w != 0 {
+        Err("Only w=0 exists in 3 dimensions".to_string())?
     }
 
+    let mut view = LiveView::new(delay)?;
+    let mut failure = None;
+    let mut last = 0;
+    let cube = simulate(parse(filename)?, three, steps, |cycle, cube| {
+        last = cycle;
+        let title = format!("Cycle {}: {} active cells, slice z={} w={}", cycle, cube.len(), z, w);
+        view.show(&title, &styled_slice(cube, z, w)).unwrap_or_else(|err| {
+            failure = Some(err);
+            false
+        })
+    });
+    drop(view);
+
+    match failure {
+        Some(err) => Err(err),
+        None if last < steps => Ok(format!("Stopped after cycle {}\nActive cells: {}", last, cube.len())),
+        None => Ok(format!("Active cells: {}", cube.len())),
+    }
+}
+
+pub fn part01(filename: &Path) -> Result<String, String> {
+    let cube = simulate(parse(filename)?, true, 6, |_, _| true);
+
+    Ok(format!("Active cells: {}", cube.len()))
+}
+
+pub fn part02(filename: &Path) -> Result<String, String> {
+    let cube = simulate(parse(filename)?, false, 6, |_, _| true);
+
     Ok(format!("Active cells: {}", cube.len()))
 }
@@ -18,11 +18,13 @@ mod day17;
 mod day18;
 mod day19;
 mod day20;
+mod render;
+mod terminal;
 
 use std::convert::{TryFrom, TryInto};
 use std::env;
 use std::error::Error;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
 #[derive(Copy, Clone, Debug)]
@@ -52,100 +54,163 @@ impl From<&Day> for i32 {
     }
 }
 
-fn main() -> Result<(), Box<dyn Error>> {
+const USAGE: &str = "usage: aoc2020 DAY-NUM | aoc2020 COMMAND DAY-NUM [ARGS...]";
+
+fn main() {
+    if let Err(err) = run() {
+        eprintln!("Error: {}", err);
+        std::process::exit(1);
+    }
+}
+
+fn run() -> Result<(), Box<dyn Error>> {
     let args: Vec<String> = env::args().collect();
 
-    if args.len() != 2 {
-        Err("usage: aoc2020 DAY-NUM".to_string())?
-    } else {
+    if args.len() == 2 {
         let day: Day = i32::from_str(&args[1])?.try_into()?;
-        let filename = generate_filename(&day);
+        solve(day, &generate_filename(&day))
+    } else if args.len() >= 3 {
+        let day: Day = i32::from_str(&args[2])?.try_into()?;
+        let output = command(&args[1], day, &generate_filename(&day), &args[3..])?;
+        if !output.is_empty() {
+            println!("{}", output);
+        }
+        Ok(())
+    } else {
+        Err(USAGE.to_string())?
+    }
+}
 
-        match day {
-            Day(1) => {
-                println!("01:01 => {}", crate::day01::part01(&filename)?);
-                println!("01:02 => {}", crate::day01::part02(&filename)?);
-            }
-            Day(2) => {
-                println!("02:01 => {}", crate::day02::part01(&filename)?);
-                println!("02:02 => {}", crate::day02::part02(&filename)?);
-            }
-            Day(3) => {
-                println!("03:01 => {}", crate::day03::part01(&filename)?);
-                println!("03:02 => {}", crate::day03::part02(&filename)?);
-            }
-            Day(4) => {
-                println!("04:01 => {}", crate::day04::part01(&filename)?);
-                println!("04:02 => {}", crate::day04::part02(&filename)?);
-            }
-            Day(5) => {
-                println!("05:01 => {}", crate::day05::part01(&filename)?);
-                println!("05:02 => {}", crate::day05::part02(&filename)?);
-            }
-            Day(6) => {
-                println!("06:01 => {}", crate::day06::part01(&filename)?);
-                println!("06:02 => {}", crate::day06::part02(&filename)?);
-            }
-            Day(7) => {
-                println!("07:01 => {}", crate::day07::part01(&filename)?);
-                println!("07:02 => {}", crate::day07::part02(&filename)?);
-            }
-            Day(8) => {
-                println!("08:01 => {}", crate::day08::part01(&filename)?);
-                println!("08:02 => {}", crate::day08::part02(&filename)?);
-            }
-            Day(9) => {
-                println!("09:01 => {}", crate::day09::part01(&filename)?);
-                println!("09:02 => {}", crate::day09::part02(&filename)?);
-            }
-            Day(10) => {
-                println!("10:01 => {}", crate::day10::part01(&filename)?);
-                println!("10:02 => {}", crate::day10::part02(&filename)?);
-            }
-            Day(11) => {
-                println!("11:01 => {}", crate::day11::part01(&filename)?);
-                println!("11:02 => {}", crate::day11::part02(&filename)?);
-            }
-            Day(12) => {
-                println!("12:01 => {}", crate::day12::part01(&filename)?);
-                println!("12:02 => {}", crate::day12::part02(&filename)?);
-            }
-            Day(13) => {
-                println!("13:01 => {}", crate::day13::part01(&filename)?);
-                println!("13:02 => {}", crate::day13::part02(&filename)?);
-            }
-            Day(14) => {
-                println!("14:01 => {}", crate::day14::part01(&filename)?);
-                println!("14:02 => {}", crate::day14::part02(&filename)?);
-            }
-            Day(15) => {
-                println!("15:01 => {}", crate::day15::part01(&filename)?);
-                println!("15:02 => {}", crate::day15::part02(&filename)?);
-            }
-            Day(16) => {
-                println!("16:01 => {}", crate::day16::part01(&filename)?);
-                println!("16:02 => {}", crate::day16::part02(&filename)?);
-            }
-            Day(17) => {
-                println!("17:01 => {}", crate::day17::part01(&filename)?);
-                println!("17:02 => {}", crate::day17::part02(&filename)?);
-            }
-            Day(18) => {
-                println!("18:01 => {}", crate::day18::part01(&filename)?);
-                println!("18:02 => {}", crate::day18::part02(&filename)?);
-            }
-            Day(19) => {
-                println!("19:01 => {}", crate::day19::part01(&filename)?);
-                println!("19:02 => {}", crate::day19::part02(&filename)?);
-            }
-            Day(20) => {
-                println!("20:01 => {}", crate::day20::part01(&filename)?);
-                println!("20:02 => {}", crate::day20::part02(&filename)?);
-            }
-            _ => println!("No solution for day {}", i32::from(&day)),
+fn solve(day: Day, filename: &Path) -> Result<(), Box<dyn Error>> {
+    match day {
+        Day(1) => {
+            println!("01:01 => {}", crate::day01::part01(filename)?);
+            println!("01:02 => {}", crate::day01::part02(filename)?);
+        }
+        Day(2) => {
+            println!("02:01 => {}", crate::day02::part01(filename)?);
+            println!("02:02 => {}", crate::day02::part02(filename)?);
+        }
+        Day(3) => {
+            println!("03:01 => {}", crate::day03::part01(filename)?);
+            println!("03:02 => {}", crate::day03::part02(filename)?);
+        }
+        Day(4) => {
+            println!("04:01 => {}", crate::day04::part01(filename)?);
+            println!("04:02 => {}", crate::day04::part02(filename)?);
+        }
+        Day(5) => {
+            println!("05:01 => {}", crate::day05::part01(filename)?);
+            println!("05:02 => {}", crate::day05::part02(filename)?);
+        }
+        Day(6) => {
+            println!("06:01 => {}", crate::day06::part01(filename)?);
+            println!("06:02 => {}", crate::day06::part02(filename)?);
+        }
+        Day(7) => {
+            println!("07:01 => {}", crate::day07::part01(filename)?);
+            println!("07:02 => {}", crate::day07::part02(filename)?);
+        }
+        Day(8) => {
+            println!("08:01 => {}", crate::day08::part01(filename)?);
+            println!("08:02 => {}", crate::day08::part02(filename)?);
         }
+        Day(9) => {
+            println!("09:01 => {}", crate::day09::part01(filename)?);
+            println!("09:02 => {}", crate::day09::part02(filename)?);
+        }
+        Day(10) => {
+            println!("10:01 => {}", crate::day10::part01(filename)?);
+            println!("10:02 => {}", crate::day10::part02(filename)?);
+        }
+        Day(11) => {
+            println!("11:01 => {}", crate::day11::part01(filename)?);
+            println!("11:02 => {}", crate::day11::part02(filename)?);
+        }
+        Day(12) => {
+            println!("12:01 => {}", crate::day12::part01(filename)?);
+            println!("12:02 => {}", crate::day12::part02(filename)?);
+        }
+        Day(13) => {
+            println!("13:01 => {}", crate::day13::part01(filename)?);
+            println!("13:02 => {}", crate::day13::part02(filename)?);
+        }
+        Day(14) => {
+            println!("14:01 => {}", crate::day14::part01(filename)?);
+            println!("14:02 => {}", crate::day14::part02(filename)?);
+        }
+        Day(15) => {
+            println!("15:01 => {}", crate::day15::part01(filename)?);
+            println!("15:02 => {}", crate::day15::part02(filename)?);
+        }
+        Day(16) => {
+            println!("16:01 => {}", crate::day16::part01(filename)?);
+            println!("16:02 => {}", crate::day16::part02(filename)?);
+        }
+        Day(17) => {
+            println!("17:01 => {}", crate::day17::part01(filename)?);
+            println!("17:02 => {}", crate::day17::part02(filename)?);
+        }
+        Day(18) => {
+            println!("18:01 => {}", crate::day18::part01(filename)?);
+            println!("18:02 => {}", crate::day18::part02(filename)?);
+        }
+        Day(19) => {
+            println!("19:01 => {}", crate::day19::part01(filename)?);
+            println!("19:02 => {}", crate::day19::part02(filename)?);
+        }
+        Day(20) => {
+            println!("20:01 => {}", crate::day20::part01(filename)?);
+            println!("20:02 => {}", crate::day20::part02(filename)?);
+        }
+        _ => println!("No solution for day {}", i32::from(&day)),
+    }
 
-        Ok(())
+    Ok(())
+}
+
+fn command(command: &str, day: Day, filename: &Path, args: &[String]) -> Result<String, String> {
+    match (command, day) {
+        ("ksum", Day(1)) => crate::day01::ksum(filename, args),
+        ("policy", Day(2)) => crate::day02::policy(filename, args),
+        ("explain", Day(2)) => crate::day02::explain(filename, args),
+        ("slopes", Day(3)) => crate::day03::slopes(filename, args),
+        ("search", Day(3)) => crate::day03::search(filename, args),
+        ("draw", Day(3)) => crate::day03::draw(filename, args),
+        ("validate", Day(4)) => crate::day04::validate(filename, args),
+        ("report", Day(4)) => crate::day04::report(filename, args),
+        ("heights", Day(4)) => crate::day04::heights(filename, args),
+        ("decode", Day(5)) => crate::day05::decode(filename, args),
+        ("encode", Day(5)) => crate::day05::encode(filename, args),
+        ("seatmap", Day(5)) => crate::day05::seatmap(filename, args),
+        ("query", Day(6)) => crate::day06::query(filename, args),
+        ("bags", Day(7)) => crate::day07::bags(filename, args),
+        ("export", Day(7)) => crate::day07::export(filename, args),
+        ("debug", Day(8)) => crate::day08::debug(filename, args),
+        ("run", Day(8)) => crate::day08::run(filename, args),
+        ("disasm", Day(8)) => crate::day08::disasm(filename, args),
+        ("repair", Day(8)) => crate::day08::repair(filename, args),
+        ("validate", Day(9)) => crate::day09::validate(filename, args),
+        ("stream", Day(9)) => crate::day09::stream(filename, args),
+        ("chain", Day(10)) => crate::day10::chain(filename, args),
+        ("arrangements", Day(10)) => crate::day10::arrangements(filename, args),
+        ("sample", Day(10)) => crate::day10::sample(filename, args),
+        ("seats", Day(11)) => crate::day11::seats(filename, args),
+        ("bench", Day(11)) => crate::day11::bench(filename, args),
+        ("animate", Day(11)) => crate::day11::animate(filename, args),
+        ("watch", Day(11)) => crate::day11::watch(filename, args),
+        ("navigate", Day(12)) => crate::day12::navigate(filename, args),
+        ("plot", Day(12)) => crate::day12::plot(filename, args),
+        ("simplify", Day(12)) => crate::day12::simplify(filename, args),
+        ("compare", Day(12)) => crate::day12::compare(filename, args),
+        ("animate", Day(17)) => crate::day17::animate(filename, args),
+        ("watch", Day(17)) => crate::day17::watch(filename, args),
+        _ => Err(format!(
+            "No command '{}' for day {}\n{}",
+            command,
+            i32::from(&day),
+            USAGE
+        )),
     }
 }
 
@@ -0,0 +1,228 @@
+use std::borrow::Cow;
+use std::convert::TryFrom;
+use std::ffi::OsStr;
+use std::fs::{self, File};
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+// colours indexed by the values frames are drawn with, index 0 is also used as background
+#[derive(Clone, Debug)]
+pub struct Palette(Vec<[u8; 3]>);
+
+impl Palette {
+    pub fn new(colours: &[[u8; 3]]) -> Self {
+        Palette(colours.to_vec())
+    }
+
+    fn rgb(&self) -> Vec<u8> {
+        self.0.iter().flatten().cloned().collect()
+    }
+}
+
+impl FromStr for Palette {
+    type Err = String;
+
+    // comma separated `RRGGBB` hex colours, e.g. `202020,40a040,d04040`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let colours = s
+            .split(',')
+            .map(|colour| {
+                let colour = colour.trim_start_matches('#');
+                let value = u32::from_str_radix(colour, 16)
+                    .ok()
+                    .filter(|_| colour.len() == 6)
+                    .ok_or(format!("Invalid colour '{}', expected RRGGBB", colour))?;
+                Ok([(value >> 16) as u8, (value >> 8) as u8, value as u8])
+            })
+            .collect::<Result<Vec<[u8; 3]>, String>>()?;
+
+        if colours.len() > 256 {
+            Err("A palette can have at most 256 colours".to_string())?
+        }
+        Ok(Palette(colours))
+    }
+}
+
+// one palette index per pixel, row by row
+#[derive(Clone, Debug)]
+pub struct Frame {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<u8>,
+}
+
+impl Frame {
+    pub fn new(width: usize, height: usize) -> Self {
+        Frame {
+            width,
+            height,
+            pixels: vec![0; width * height],
+        }
+    }
+
+    // fills the `scale` by `scale` block for cell (x, y)
+    pub fn cell(&mut self, x: usize, y: usize, scale: usize, colour: u8) {
+        for py in y * scale..(y + 1) * scale {
+            let row = py * self.width;
+            for px in x * scale..(x + 1) * scale {
+                self.pixels[row + px] = colour;
+            }
+        }
+    }
+
+    // a copy padded with the background colour to `width` by `height`
+    fn padded(&self, width: usize, height: usize) -> Cow<'_, [u8]> {
+        if width == self.width && height == self.height {
+            return Cow::Borrowed(&self.pixels);
+        }
+
+        let mut pixels = vec![0; width * height];
+        for (y, row) in self.pixels.chunks(self.width.max(1)).enumerate() {
+            pixels[y * width..y * width + self.width].copy_from_slice(row);
+        }
+        Cow::Owned(pixels)
+    }
+}
+
+// collects frames and writes them as an animated GIF when `path` ends in `.gif`, otherwise as numbered
+// PNG files in the directory `path`
+pub struct Recorder {
+    path: PathBuf,
+    palette: Palette,
+    // hundredths of a second between GIF frames
+    delay: u16,
+    frames: Vec<Frame>,
+}
+
+impl Recorder {
+    pub fn new(path: &Path, palette: Palette, delay: u16) -> Self {
+        Recorder {
+            path: path.to_path_buf(),
+            palette,
+            delay,
+            frames: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, frame: Frame) {
+        self.frames.push(frame);
+    }
+
+    // writes every frame, smaller frames are padded to the largest one, and returns a description of the output
+    pub fn finish(self) -> Result<String, String> {
+        let width = self.frames.iter().map(|frame| frame.width).max().unwrap_or(0);
+        let height = self.frames.iter().map(|frame| frame.height).max().unwrap_or(0);
+        if width == 0 || height == 0 {
+            Err("Nothing to render".to_string())?
+        }
+
+        if self.path.extension() == Some(OsStr::new("gif")) {
+            self.write_gif(width, height)?;
+            Ok(format!(
+                "Wrote {} frames of {}x{} to {}",
+                self.frames.len(),
+                width,
+                height,
+                self.path.display()
+            ))
+        } else {
+            self.write_pngs(width, height)?;
+            Ok(format!(
+                "Wrote {} frames of {}x{} to {}/frame-NNNN.png",
+                self.frames.len(),
+                width,
+                height,
+                self.path.display()
+            ))
+        }
+    }
+
+    fn write_gif(&self, width: usize, height: usize) -> Result<(), String> {
+        if width > u16::MAX as usize || height > u16::MAX as usize {
+            Err(format!("{}x{} is too large for a GIF", width, height))?
+        }
+        let file =
+            File::create(&self.path).map_err(|err| format!("Failed to create {}: {}", self.path.display(), err))?;
+        let error = |err: gif::EncodingError| format!("Failed to write {}: {}", self.path.display(), err);
+
+        let mut encoder =
+            gif::Encoder::new(BufWriter::new(file), width as u16, height as u16, &self.palette.rgb()).map_err(error)?;
+        encoder.set_repeat(gif::Repeat::Infinite).map_err(error)?;
+        for frame in self.frames.iter() {
+            let gif_frame = gif::Frame {
+                width: width as u16,
+                height: height as u16,
+                delay: self.delay,
+                buffer: frame.padded(width, height),
+                ..gif::Frame::default()
+            };
+            encoder.write_frame(&gif_frame).map_err(error)?;
+        }
+
+        Ok(())
+    }
+
+    fn write_pngs(&self, width: usize, height: usize) -> Result<(), String> {
+        fs::create_dir_all(&self.path).map_err(|err| format!("Failed to create {}: {}", self.path.display(), err))?;
+
+        for (idx, frame) in self.frames.iter().enumerate() {
+            let path = self.path.join(format!("frame-{:04}.png", idx));
+            let file = File::create(&path).map_err(|err| format!("Failed to create {}: {}", path.display(), err))?;
+            let error = |err: png::EncodingError| format!("Failed to write {}: {}", path.display(), err);
+
+            let mut encoder = png::Encoder::new(BufWriter::new(file), width as u32, height as u32);
+            encoder.set_color(png::ColorType::Indexed);
+            encoder.set_depth(png::BitDepth::Eight);
+            encoder.set_palette(self.palette.rgb());
+            encoder
+                .write_header()
+                .and_then(|mut writer| writer.write_image_data(&frame.padded(width, height)))
+                .map_err(error)?;
+        }
+
+        Ok(())
+    }
+}
+
+pub struct Options {
+    pub scale: usize,
+    pub palette: Palette,
+    // hundredths of a second between frames
+    pub delay: u16,
+}
+
+// options shared by the rendering commands, `scale=N`, `palette=RRGGBB,..` and `delay=MILLISECONDS` (as for the
+// terminal views, rounded to the hundredths GIF frames store), with the remaining arguments returned untouched
+pub fn parse_options(args: &[String], palette: Palette) -> Result<(Options, Vec<String>), String> {
+    let mut options = Options {
+        scale: 4,
+        palette,
+        delay: 10,
+    };
+    let mut rest = Vec::new();
+
+    for arg in args {
+        if let Some(value) = arg.strip_prefix("scale=") {
+            options.scale = match usize::from_str(value) {
+                Ok(0) => Err("The scale must be positive".to_string())?,
+                Ok(scale) => scale,
+                Err(err) => Err(format!("Invalid scale '{}': {}", value, err))?,
+            };
+        } else if let Some(value) = arg.strip_prefix("palette=") {
+            let custom = Palette::from_str(value)?;
+            if custom.0.len() < options.palette.0.len() {
+                Err(format!("The palette needs {} colours", options.palette.0.len()))?
+            }
+            options.palette = custom;
+        } else if let Some(value) = arg.strip_prefix("delay=") {
+            let millis = u32::from_str(value).map_err(|err| format!("Invalid delay '{}': {}", value, err))?;
+            options.delay = u16::try_from((millis as u64 + 5) / 10)
+                .map_err(|_| format!("The delay must be at most {}ms", u16::MAX as u32 * 10))?;
+        } else {
+            rest.push(arg.clone());
+        }
+    }
+
+    Ok((options, rest))
+}
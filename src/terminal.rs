@@ -0,0 +1,155 @@
+use std::io::{self, Stdout, Write};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::style::{Color, Print, ResetColor, SetForegroundColor};
+use crossterm::terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::{execute, queue};
+
+const HELP: &str = "space: pause/resume  n: step  +/-: slower/faster  q: quit";
+
+enum Key {
+    Pause,
+    Step,
+    Slower,
+    Faster,
+    Quit,
+}
+
+fn key(event: Event) -> Option<Key> {
+    match event {
+        Event::Key(KeyEvent {
+            code,
+            modifiers,
+            kind: KeyEventKind::Press,
+            ..
+        }) => match code {
+            KeyCode::Char(' ') => Some(Key::Pause),
+            KeyCode::Char('n') | KeyCode::Right => Some(Key::Step),
+            KeyCode::Char('+') => Some(Key::Slower),
+            KeyCode::Char('-') => Some(Key::Faster),
+            KeyCode::Char('q') | KeyCode::Esc => Some(Key::Quit),
+            KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => Some(Key::Quit),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+// redraws frames in place on the alternate screen, restoring the terminal when dropped
+pub struct LiveView {
+    out: Stdout,
+    delay: Duration,
+    paused: bool,
+    quit: bool,
+}
+
+impl LiveView {
+    pub fn new(delay: Duration) -> Result<Self, String> {
+        let mut out = io::stdout();
+        terminal::enable_raw_mode().map_err(|err| format!("Failed to set up the terminal: {}", err))?;
+        execute!(out, EnterAlternateScreen, Hide).map_err(|err| format!("Failed to set up the terminal: {}", err))?;
+
+        Ok(LiveView {
+            out,
+            delay,
+            paused: false,
+            quit: false,
+        })
+    }
+
+    // draws `title` above rows of coloured characters, then waits for the frame delay or a key press, returns
+    // false once the viewer has quit
+    pub fn show(&mut self, title: &str, rows: &[Vec<(char, Color)>]) -> Result<bool, String> {
+        if self.quit {
+            return Ok(false);
+        }
+        self.draw(title, rows)
+            .map_err(|err| format!("Failed to draw to the terminal: {}", err))?;
+        self.wait()
+            .map_err(|err| format!("Failed to read the terminal: {}", err))?;
+        Ok(!self.quit)
+    }
+
+    fn draw(&mut self, title: &str, rows: &[Vec<(char, Color)>]) -> io::Result<()> {
+        queue!(self.out, MoveTo(0, 0), Clear(ClearType::All), Print(title))?;
+        for (y, row) in rows.iter().enumerate() {
+            queue!(self.out, MoveTo(0, y as u16 + 1))?;
+            let mut current = None;
+            for &(c, colour) in row {
+                if current != Some(colour) {
+                    queue!(self.out, SetForegroundColor(colour))?;
+                    current = Some(colour);
+                }
+                queue!(self.out, Print(c))?;
+            }
+        }
+        let status = if self.paused { "paused" } else { "running" };
+        queue!(
+            self.out,
+            ResetColor,
+            MoveTo(0, rows.len() as u16 + 1),
+            Print(format!("{} ({}ms/frame)  {}", status, self.delay.as_millis(), HELP))
+        )?;
+        self.out.flush()
+    }
+
+    fn wait(&mut self) -> io::Result<()> {
+        let deadline = Instant::now() + self.delay;
+        loop {
+            let event = if self.paused {
+                Some(event::read()?)
+            } else {
+                let left = deadline.saturating_duration_since(Instant::now());
+                if left.is_zero() || !event::poll(left)? {
+                    return Ok(());
+                }
+                Some(event::read()?)
+            };
+
+            match event.and_then(key) {
+                Some(Key::Pause) => {
+                    self.paused = !self.paused;
+                    if !self.paused {
+                        return Ok(());
+                    }
+                }
+                Some(Key::Step) if self.paused => return Ok(()),
+                Some(Key::Slower) => self.delay += Duration::from_millis(25),
+                Some(Key::Faster) => self.delay = self.delay.saturating_sub(Duration::from_millis(25)),
+                Some(Key::Quit) => {
+                    self.quit = true;
+                    return Ok(());
+                }
+                _ => (),
+            }
+        }
+    }
+}
+
+impl Drop for LiveView {
+    fn drop(&mut self) {
+        let _ = execute!(self.out, ResetColor, Show, LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+// the shared `delay=MILLISECONDS` option, with the remaining arguments returned untouched
+pub fn parse_delay(args: &[String]) -> Result<(Duration, Vec<String>), String> {
+    let mut delay = Duration::from_millis(100);
+    let mut rest = Vec::new();
+
+    for arg in args {
+        if let Some(value) = arg.strip_prefix("delay=") {
+            delay = Duration::from_millis(
+                u64::from_str(value).map_err(|err| format!("Invalid delay '{}': {}", value, err))?,
+            );
+        } else {
+            rest.push(arg.clone());
+        }
+    }
+
+    Ok((delay, rest))
+}